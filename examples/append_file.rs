@@ -1,5 +1,5 @@
 use failure::{format_err, Error};
-use hedera::{Client, Status};
+use hedera::{Client, SecretKey, Status};
 use std::{env, thread::sleep, time::Duration};
 
 #[tokio::main]
@@ -22,7 +22,7 @@ async fn main() -> Result<(), Error> {
 
     let id = client
         .append_file(file, file_extra_bytes)
-        .sign(&env::var("OPERATOR_SECRET")?.parse()?) // sign as the owner of the file to approve the change
+        .sign(&env::var("OPERATOR_SECRET")?.parse::<SecretKey>()?) // sign as the owner of the file to approve the change
         .execute_async()
         .await?;
 
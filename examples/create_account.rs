@@ -10,7 +10,7 @@ async fn main() -> Result<(), Error> {
     let (secret, _) = SecretKey::generate("");
     let public = secret.public();
 
-    println!("secret = {}", secret);
+    println!("secret = {}", *secret.to_encoded_hex());
     println!("public = {}", public);
 
     // Operator is the account that sends the transaction to the network
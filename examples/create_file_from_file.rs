@@ -34,7 +34,7 @@ async fn main() -> Result<(), Error> {
         .key(public)
         .contents(file_contents)
         .memo("[hedera-sdk-rust][example] create_file")
-        .sign(&env::var("OPERATOR_SECRET")?.parse()?) // sign as the owner of the file
+        .sign(&env::var("OPERATOR_SECRET")?.parse::<SecretKey>()?) // sign as the owner of the file
         .execute_async()
         .await?;
 
@@ -0,0 +1,37 @@
+use failure::{format_err, Error};
+use hedera::{call_params::CallParams, Client};
+use std::{env, fs};
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    pretty_env_logger::try_init()?;
+
+    // Path to the compiled contract, as produced by `solc --bin`
+    let path = env::args()
+        .nth(1)
+        .ok_or_else(|| format_err!("usage: deploy_contract <path to .bin>"))?;
+
+    let bytecode = hex::decode(fs::read_to_string(path)?.trim())?;
+
+    // Operator is the account that sends the transaction to the network
+    // This account is charged for the transaction fee
+    let operator = "0:0:2".parse()?;
+    let client = Client::builder("testnet.hedera.com:50003")
+        .node("0:0:3".parse()?)
+        .operator(operator, || env::var("OPERATOR_SECRET"))
+        .build()?;
+
+    let mut params = CallParams::new(None);
+    params.add_string("hello from rust".to_string());
+
+    let contract = client
+        .deploy_contract(bytecode, params)
+        .gas(100_000_000)
+        .delete_file(true)
+        .execute_async()
+        .await?;
+
+    println!("contract ID = {}", contract);
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ fn main() {
     let (secret, mnemonic) = SecretKey::generate("");
     let public = secret.public();
 
-    println!("secret   = {}", secret);
-    println!("mnemonic = {}", mnemonic);
+    println!("secret   = {}", *secret.to_encoded_hex());
+    println!("mnemonic = {}", mnemonic.phrase());
     println!("public   = {}", public);
 }
@@ -0,0 +1,38 @@
+use failure::Error;
+use hedera::{Client, Keystore};
+use std::{env, path::Path};
+
+fn main() -> Result<(), Error> {
+    pretty_env_logger::try_init()?;
+
+    let path = Path::new("keystore.json");
+    let password = env::var("KEYSTORE_PASSWORD")?;
+
+    // Store the operator key encrypted, once; afterwards, only the password is needed
+
+    if !path.exists() {
+        let mut keystore = Keystore::new();
+        keystore.insert(
+            "operator",
+            env::var("OPERATOR")?.parse()?,
+            env::var("OPERATOR_SECRET")?.parse()?,
+        )?;
+        keystore.save(path, &password)?;
+    }
+
+    let keystore = Keystore::open(path, &password)?;
+    let client = Client::builder("testnet.hedera.com:50131")
+        .node("0:0:3".parse()?)
+        .operator_from_keystore(&keystore, "operator")?
+        .build()?;
+
+    let operator = keystore.get("operator").unwrap().account;
+
+    // Get _just_ the balance for the account
+    // This costs 100,000 tinybar
+
+    let balance = client.account(operator).balance().get()?;
+    println!("balance = {} tinybars", balance);
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use failure::{format_err, Error};
-use hedera::{AccountId, Client, Status};
+use hedera::{AccountId, Client, SecretKey, Status};
 use std::{env, thread::sleep, time::Duration};
 
 #[tokio::main]
@@ -23,8 +23,8 @@ async fn main() -> Result<(), Error> {
         .transfer(operator, -1_000_000)
         .transfer(receiver, 1_000_000)
         .memo("[hedera-sdk-rust][example] transfer_crypto")
-        .sign(&env::var("OPERATOR_SECRET")?.parse()?)
-        .sign(&env::var("OPERATOR_SECRET")?.parse()?)
+        .sign(&env::var("OPERATOR_SECRET")?.parse::<SecretKey>()?)
+        .sign(&env::var("OPERATOR_SECRET")?.parse::<SecretKey>()?)
         .execute_async()
         .await?;
 
@@ -1,6 +1,6 @@
 use failure::{format_err, Error};
 use futures::FutureExt;
-use hedera::{Client, Status};
+use hedera::{Client, SecretKey, Status};
 use std::{env, thread::sleep, time::Duration};
 
 #[tokio::main]
@@ -25,7 +25,7 @@ async fn main() -> Result<(), Error> {
         .auto_renew_period(Duration::from_secs(1000))
         // .expires_at(expiration: DateTime<Utc>)
         .expires_in(Duration::from_secs(2_592_000))
-        .sign(&env::var("OPERATOR_SECRET")?.parse()?) // sign as the owner of the account to approve the change
+        .sign(&env::var("OPERATOR_SECRET")?.parse::<SecretKey>()?) // sign as the owner of the account to approve the change
         .execute_async()
         .await?;
 
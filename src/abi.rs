@@ -0,0 +1,19 @@
+// Solidity contract ABI types, values and (de-)coding
+mod bindgen;
+mod contract_abi;
+mod decode;
+mod encode;
+mod log;
+mod param_type;
+mod revert;
+mod value;
+
+pub use self::{
+    bindgen::*, contract_abi::*, decode::*, encode::*, log::*, param_type::*, revert::*, value::*,
+};
+
+// Re-exported for use by generated bindings
+pub use num::{BigInt, BigUint, ToPrimitive};
+
+// Every ABI value is encoded in one or more 32-byte words
+pub(crate) const WORD_LEN: usize = 32;
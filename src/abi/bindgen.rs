@@ -0,0 +1,830 @@
+use crate::abi::{ContractAbi, Event, Function, ParamType};
+use failure::Error;
+use std::{collections::HashSet, fs, path::Path};
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Typed Rust bindings for a contract, generated from its ABI.
+///
+/// Intended to be used from a build script, with the generated source included in the crate:
+///
+/// ```ignore
+/// // build.rs
+/// let out = Path::new(&env::var("OUT_DIR")?).join("token.rs");
+/// Bindings::from_file("Token", "contracts/Token.abi")?.write_to_file(out)?;
+///
+/// // src/lib.rs
+/// include!(concat!(env!("OUT_DIR"), "/token.rs"));
+/// ```
+///
+/// The generated `Token` struct wraps a `ContractId` and has, for every contract function `f`:
+///
+///  * `f_params(..)` to build the `CallParams` for a call,
+///  * `f(&client, ..)` to build a `TransactionContractCall`,
+///  * `f_local(&client, ..)` to build a local `QueryContractCall`, and
+///  * `decode_f(&result)` to decode the typed return value from a `ContractFunctionResult`.
+///
+/// Every event `E` gets an `EEvent` struct with a field per parameter, decoded from a
+/// `ContractLogInfo` with `EEvent::decode_log(&log)`.
+///
+/// Overloaded functions and events after the first are numbered; `f_1(..)` and `E1Event`.
+/// So is any function or event whose generated names would clash with earlier ones (such as
+/// `balanceOf` and `balance_of`, or `foo` and `foo_local`), until the names are unique.
+pub struct Bindings {
+    name: String,
+    abi: ContractAbi,
+}
+
+impl Bindings {
+    /// Bindings for the contract `name`; the name of the generated struct.
+    pub fn new(name: impl Into<String>, abi: ContractAbi) -> Self {
+        Self {
+            name: name.into(),
+            abi,
+        }
+    }
+
+    pub fn from_file(name: impl Into<String>, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let abi = ContractAbi::from_reader(fs::File::open(path)?)?;
+        Ok(Self::new(name, abi))
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        fs::write(path, self.generate())?;
+        Ok(())
+    }
+
+    /// Generate the Rust source of the bindings.
+    pub fn generate(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(&format!(
+            "/// Typed bindings for the `{name}` contract.\n\
+             #[derive(Debug, Clone, Copy, PartialEq)]\n\
+             pub struct {name} {{\n    \
+                 pub id: ::hedera::ContractId,\n\
+             }}\n\n\
+             impl {name} {{\n    \
+                 pub fn new(id: ::hedera::ContractId) -> Self {{\n        \
+                     Self {{ id }}\n    \
+                 }}\n",
+            name = self.name
+        ));
+
+        if let Some(constructor) = &self.abi.constructor {
+            let args = arg_names(constructor.inputs.iter().map(|param| &*param.name));
+            let types: Vec<&ParamType> =
+                constructor.inputs.iter().map(|param| &param.kind).collect();
+
+            out.push_str(&format!(
+                "\n    /// Build the constructor parameters for deploying the contract.\n    \
+                 pub fn constructor_params({args}) -> {params_result} {{\n        \
+                     let {mut_}params = {call_params}::new(None);\n\
+                     {add_values}        \
+                     Ok(params)\n    \
+                 }}\n",
+                args = arg_list(&args, &types),
+                mut_ = if args.is_empty() { "" } else { "mut " },
+                call_params = CALL_PARAMS,
+                params_result = PARAMS_RESULT,
+                add_values = add_values(&args, &types),
+            ));
+        }
+
+        for (function, method) in self.functions() {
+            out.push_str(&generate_function(function, &method));
+        }
+
+        out.push_str("}\n");
+
+        for (event, name) in self.events() {
+            out.push_str(&generate_event(&self.name, event, &name));
+        }
+
+        out
+    }
+
+    // Functions with the names of their methods; overloads after the first, and functions whose
+    // methods would clash with those already generated, are numbered
+    fn functions(&self) -> Vec<(&Function, String)> {
+        let mut used: HashSet<String> = HashSet::new();
+        used.insert("new".to_string());
+
+        if self.abi.constructor.is_some() {
+            used.insert("constructor_params".to_string());
+        }
+
+        let mut functions = Vec::new();
+
+        for (i, function) in self.abi.functions.iter().enumerate() {
+            let overload = self.abi.functions[..i]
+                .iter()
+                .filter(|f| f.name == function.name)
+                .count();
+
+            let name = snake_case(&function.name);
+
+            let method = (overload..)
+                .map(|n| {
+                    let mut method = match n {
+                        0 => name.clone(),
+                        _ => format!("{}_{}", name, n),
+                    };
+
+                    if method == "new" || KEYWORDS.contains(&&*method) {
+                        method.push('_');
+                    }
+
+                    method
+                })
+                .find(|method| method_names(method).iter().all(|name| !used.contains(name)))
+                // NOTE: Not possible to fail; there are only finitely many names in use
+                .unwrap();
+
+            used.extend(method_names(&method).iter().cloned());
+            functions.push((function, method));
+        }
+
+        functions
+    }
+
+    // Events with the names of their structs; overloads after the first, and events whose
+    // structs would clash with those already generated, are numbered
+    fn events(&self) -> Vec<(&Event, String)> {
+        let mut used: HashSet<String> = HashSet::new();
+        used.insert(self.name.clone());
+
+        let mut events = Vec::new();
+
+        for (i, event) in self.abi.events.iter().enumerate() {
+            let overload = self.abi.events[..i]
+                .iter()
+                .filter(|e| e.name == event.name)
+                .count();
+
+            let name = pascal_case(&event.name);
+
+            let name = (overload..)
+                .map(|n| match n {
+                    0 => format!("{}Event", name),
+                    _ => format!("{}{}Event", name, n),
+                })
+                .find(|name| !used.contains(name))
+                // NOTE: Not possible to fail; there are only finitely many names in use
+                .unwrap();
+
+            used.insert(name.clone());
+            events.push((event, name));
+        }
+
+        events
+    }
+}
+
+// Every method generated for a contract function
+fn method_names(method: &str) -> [String; 4] {
+    [
+        method.to_string(),
+        format!("{}_params", method),
+        format!("{}_local", method),
+        format!("decode_{}", method),
+    ]
+}
+
+const CALL_PARAMS: &str = "::hedera::call_params::CallParams";
+
+const PARAMS_RESULT: &str = "Result<::hedera::call_params::CallParams, ::hedera::AbiError>";
+
+const TRANSACTION: &str =
+    "::hedera::transaction::Transaction<::hedera::transaction::TransactionContractCall>";
+
+const QUERY: &str = "::hedera::query::Query<::hedera::query::QueryContractCall>";
+
+fn generate_function(function: &Function, method: &str) -> String {
+    let args = arg_names(function.inputs.iter().map(|param| &*param.name));
+    let types: Vec<&ParamType> = function.inputs.iter().map(|param| &param.kind).collect();
+    let outputs: Vec<&ParamType> = function.outputs.iter().map(|param| &param.kind).collect();
+
+    let signature = function.signature();
+    let arg_list = arg_list(&args, &types);
+    let arg_names = args.join(", ");
+
+    let decode = match outputs.len() {
+        0 => "        result.decode(&[])?;\n        Ok(())\n".to_string(),
+
+        _ => format!(
+            "        let mut values = result.decode(&[{types}])?.into_iter();\n        \
+             Ok({values})\n",
+            types = type_exprs(&outputs),
+            values = tuple_or_single(
+                outputs
+                    .iter()
+                    .map(|ty| from_value(ty, "values.next().unwrap()", 0))
+                    .collect()
+            ),
+        ),
+    };
+
+    format!(
+        "\n    /// Build the parameters for a call of `{signature}`.\n    \
+         pub fn {method}_params({arg_list}) -> {params_result} {{\n        \
+             let {mut_}params = {call_params}::new(Some({name:?}.to_string()));\n\
+             {add_values}        \
+             Ok(params)\n    \
+         }}\n\n    \
+         /// Call `{signature}` in a transaction.\n    \
+         pub fn {method}(\n        \
+             &self,\n        \
+             client: &::hedera::Client,\n\
+             {arg_list_comma}    \
+         ) -> Result<{transaction}, ::hedera::AbiError> {{\n        \
+             let mut tx = client.call_contract(self.id);\n        \
+             tx.function_parameters(Self::{method}_params({arg_names})?.assemble());\n        \
+             Ok(tx)\n    \
+         }}\n\n    \
+         /// Call `{signature}` on a single node, without reaching consensus.\n    \
+         pub fn {method}_local(\n        \
+             &self,\n        \
+             client: &::hedera::Client,\n\
+             {arg_list_comma}    \
+         ) -> Result<{query}, ::hedera::AbiError> {{\n        \
+             Ok(client.contract(self.id).call_local(Self::{method}_params({arg_names})?))\n    \
+         }}\n\n    \
+         /// Decode the result of a call of `{signature}`.\n    \
+         pub fn decode_{method}(\n        \
+             result: &::hedera::function_result::ContractFunctionResult,\n    \
+         ) -> Result<{output}, ::hedera::AbiError> {{\n\
+             {decode}    \
+         }}\n",
+        signature = signature,
+        method = method,
+        name = function.name,
+        arg_list = arg_list,
+        arg_list_comma = if args.is_empty() {
+            String::new()
+        } else {
+            format!("        {},\n", arg_list)
+        },
+        mut_ = if args.is_empty() { "" } else { "mut " },
+        call_params = CALL_PARAMS,
+        transaction = TRANSACTION,
+        query = QUERY,
+        arg_names = arg_names,
+        params_result = PARAMS_RESULT,
+        add_values = add_values(&args, &types),
+        output = tuple_or_single(outputs.iter().map(|ty| rust_type(ty)).collect()),
+        decode = decode,
+    )
+}
+
+fn generate_event(contract: &str, event: &Event, name: &str) -> String {
+    let fields = arg_names(event.inputs.iter().map(|param| &*param.name));
+
+    let declarations: String = fields
+        .iter()
+        .zip(&event.inputs)
+        .map(|(field, param)| format!("    pub {}: {},\n", field, rust_type(&param.kind)))
+        .collect();
+
+    let from_values = if fields.is_empty() {
+        "        Ok(Self {})\n".to_string()
+    } else {
+        let values: String = fields
+            .iter()
+            .zip(&event.inputs)
+            .map(|(field, param)| {
+                format!(
+                    "            {}: {},\n",
+                    field,
+                    from_value(&param.kind, "values.next().unwrap()", 0)
+                )
+            })
+            .collect();
+
+        format!(
+            "        let mut values = values.into_iter();\n\n        \
+             Ok(Self {{\n\
+             {}        \
+             }})\n",
+            values
+        )
+    };
+
+    let inputs: String = event
+        .inputs
+        .iter()
+        .map(|param| {
+            format!(
+                "                ::hedera::abi::EventParam {{ name: {:?}.to_string(), kind: {}, \
+                 indexed: {} }},\n",
+                param.name,
+                type_expr(&param.kind),
+                param.indexed
+            )
+        })
+        .collect();
+
+    format!(
+        "\n/// The `{signature}` event of the `{contract}` contract.\n\
+         #[derive(Debug, Clone, PartialEq)]\n\
+         pub struct {name} {{\n\
+         {declarations}\
+         }}\n\n\
+         impl {name} {{\n    \
+             pub const SIGNATURE: &'static str = {signature:?};\n\n    \
+             /// Build the event from the values of its parameters, in declaration order.\n    \
+             pub fn from_values(\n        \
+                 values: Vec<::hedera::abi::AbiValue>,\n    \
+             ) -> Result<Self, ::hedera::AbiError> {{\n        \
+                 if values.len() != {len} {{\n            \
+                     return Err(::hedera::AbiError::ArgumentCount {{\n                \
+                         expected: {len},\n                \
+                         found: values.len(),\n            \
+                     }});\n        \
+                 }}\n\n\
+                 {from_values}    \
+             }}\n\n    \
+             /// Decode the event from a log emitted by the contract.\n    \
+             pub fn decode_log(\n        \
+                 log: &::hedera::function_result::ContractLogInfo,\n    \
+             ) -> Result<Self, ::hedera::AbiError> {{\n        \
+                 Self::from_values(Self::event().decode_log(log)?)\n    \
+             }}\n\n    \
+             /// The description of the event in the contract ABI.\n    \
+             pub fn event() -> ::hedera::abi::Event {{\n        \
+                 ::hedera::abi::Event {{\n            \
+                     name: {event_name:?}.to_string(),\n            \
+                     inputs: vec![\n\
+                     {inputs}            \
+                     ],\n            \
+                     anonymous: {anonymous},\n        \
+                 }}\n    \
+             }}\n\
+         }}\n",
+        signature = event.signature(),
+        contract = contract,
+        name = name,
+        declarations = declarations,
+        len = fields.len(),
+        from_values = from_values,
+        event_name = event.name,
+        inputs = inputs,
+        anonymous = event.anonymous,
+    )
+}
+
+// Rust names for parameters, in order; unnamed parameters are numbered
+fn arg_names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+
+    for (i, name) in names.enumerate() {
+        let mut arg = snake_case(name);
+
+        if arg.is_empty() || args.contains(&arg) {
+            arg = format!("arg{}", i);
+        }
+
+        // `client` is taken by the generated methods
+        if arg == "client" || KEYWORDS.contains(&&*arg) {
+            arg.push('_');
+        }
+
+        args.push(arg);
+    }
+
+    args
+}
+
+fn arg_list(args: &[String], types: &[&ParamType]) -> String {
+    let args: Vec<String> = args
+        .iter()
+        .zip(types)
+        .map(|(arg, ty)| format!("{}: {}", arg, rust_type(ty)))
+        .collect();
+
+    args.join(", ")
+}
+
+fn add_values(args: &[String], types: &[&ParamType]) -> String {
+    args.iter()
+        .zip(types)
+        .map(|(arg, ty)| {
+            format!(
+                "        params.add_value(&{}, &{})?;\n",
+                type_expr(ty),
+                into_value(ty, arg, 0)
+            )
+        })
+        .collect()
+}
+
+fn tuple_or_single(mut items: Vec<String>) -> String {
+    match items.len() {
+        1 => items.remove(0),
+        _ => tuple(&items),
+    }
+}
+
+fn tuple(items: &[String]) -> String {
+    match items.len() {
+        1 => format!("({},)", items[0]),
+        _ => format!("({})", items.join(", ")),
+    }
+}
+
+// The Rust type used for values of a Solidity type
+fn rust_type(ty: &ParamType) -> String {
+    match ty {
+        ParamType::Address => "::hedera::SolidityAddress".to_string(),
+
+        ParamType::FixedBytes(_) | ParamType::Bytes | ParamType::Function => {
+            "Vec<u8>".to_string()
+        }
+
+        ParamType::Bool => "bool".to_string(),
+        ParamType::String => "String".to_string(),
+        ParamType::Int(width) => int_type("i", *width).unwrap_or("::hedera::abi::BigInt").into(),
+        ParamType::Uint(width) => int_type("u", *width).unwrap_or("::hedera::abi::BigUint").into(),
+
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) => {
+            format!("Vec<{}>", rust_type(inner))
+        }
+
+        ParamType::Tuple(members) => {
+            tuple(&members.iter().map(rust_type).collect::<Vec<_>>())
+        }
+    }
+}
+
+// The smallest primitive integer type that holds `width` bits, if there is one
+fn int_type(prefix: &str, width: usize) -> Option<&'static str> {
+    let types: [&'static str; 5] = match prefix {
+        "i" => ["i8", "i16", "i32", "i64", "i128"],
+        _ => ["u8", "u16", "u32", "u64", "u128"],
+    };
+
+    [8, 16, 32, 64, 128]
+        .iter()
+        .position(|bits| width <= *bits)
+        .map(|i| types[i])
+}
+
+// An expression that constructs the `ParamType`
+fn type_expr(ty: &ParamType) -> String {
+    const PATH: &str = "::hedera::abi::ParamType";
+
+    match ty {
+        ParamType::Address => format!("{}::Address", PATH),
+        ParamType::Bool => format!("{}::Bool", PATH),
+        ParamType::Int(width) => format!("{}::Int({})", PATH, width),
+        ParamType::Uint(width) => format!("{}::Uint({})", PATH, width),
+        ParamType::FixedBytes(len) => format!("{}::FixedBytes({})", PATH, len),
+        ParamType::Bytes => format!("{}::Bytes", PATH),
+        ParamType::String => format!("{}::String", PATH),
+        ParamType::Function => format!("{}::Function", PATH),
+        ParamType::Array(inner) => format!("{}::Array(Box::new({}))", PATH, type_expr(inner)),
+
+        ParamType::FixedArray(inner, len) => {
+            format!("{}::FixedArray(Box::new({}), {})", PATH, type_expr(inner), len)
+        }
+
+        ParamType::Tuple(members) => {
+            let members: Vec<&ParamType> = members.iter().collect();
+            format!("{}::Tuple(vec![{}])", PATH, type_exprs(&members))
+        }
+    }
+}
+
+fn type_exprs(types: &[&ParamType]) -> String {
+    let types: Vec<String> = types.iter().map(|ty| type_expr(ty)).collect();
+    types.join(", ")
+}
+
+// An expression that converts the Rust value `expr` into an `AbiValue`;
+// `depth` keeps the names of nested bindings apart
+fn into_value(ty: &ParamType, expr: &str, depth: usize) -> String {
+    const PATH: &str = "::hedera::abi::AbiValue";
+
+    match ty {
+        ParamType::Address => format!("{}::Address({})", PATH, expr),
+        ParamType::Bool => format!("{}::Bool({})", PATH, expr),
+        ParamType::Int(_) => format!("{}::Int({}.into())", PATH, expr),
+        ParamType::Uint(_) => format!("{}::Uint({}.into())", PATH, expr),
+        ParamType::FixedBytes(_) => format!("{}::FixedBytes({})", PATH, expr),
+        ParamType::Bytes => format!("{}::Bytes({})", PATH, expr),
+        ParamType::String => format!("{}::String({})", PATH, expr),
+        ParamType::Function => format!("{}::Function({})", PATH, expr),
+
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) => {
+            let variant = match ty {
+                ParamType::Array(_) => "Array",
+                _ => "FixedArray",
+            };
+
+            let element = format!("v{}", depth);
+
+            format!(
+                "{}::{}({}.into_iter().map(|{}| {}).collect())",
+                PATH,
+                variant,
+                expr,
+                element,
+                into_value(inner, &element, depth + 1)
+            )
+        }
+
+        ParamType::Tuple(members) => {
+            let names: Vec<String> =
+                (0..members.len()).map(|i| format!("t{}_{}", depth, i)).collect();
+
+            let values: Vec<String> = members
+                .iter()
+                .zip(&names)
+                .map(|(member, name)| into_value(member, name, depth + 1))
+                .collect();
+
+            format!(
+                "{{ let {} = {}; {}::Tuple(vec![{}]) }}",
+                tuple(&names),
+                expr,
+                PATH,
+                values.join(", ")
+            )
+        }
+    }
+}
+
+// An expression that converts the `AbiValue` `expr` into its Rust type; used where `?` can
+// return an `AbiError`
+fn from_value(ty: &ParamType, expr: &str, depth: usize) -> String {
+    match ty {
+        ParamType::Address => format!("{}.into_address()?", expr),
+        ParamType::Bool => format!("{}.into_bool()?", expr),
+        ParamType::String => format!("{}.into_string()?", expr),
+
+        ParamType::FixedBytes(_) | ParamType::Bytes | ParamType::Function => {
+            format!("{}.into_bytes()?", expr)
+        }
+
+        ParamType::Int(width) => match int_type("i", *width) {
+            Some(int) => to_primitive(&format!("{}.into_int()?", expr), int),
+            None => format!("{}.into_int()?", expr),
+        },
+
+        ParamType::Uint(width) => match int_type("u", *width) {
+            Some(int) => to_primitive(&format!("{}.into_uint()?", expr), int),
+            None => format!("{}.into_uint()?", expr),
+        },
+
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) => {
+            let element = format!("v{}", depth);
+
+            format!(
+                "{}.into_array()?.into_iter().map(|{}| Ok({})).collect::<Result<Vec<_>, \
+                 ::hedera::AbiError>>()?",
+                expr,
+                element,
+                from_value(inner, &element, depth + 1)
+            )
+        }
+
+        ParamType::Tuple(members) => {
+            let name = format!("t{}", depth);
+            let next = format!("{}.next().unwrap()", name);
+
+            let values: Vec<String> =
+                members.iter().map(|member| from_value(member, &next, depth + 1)).collect();
+
+            format!(
+                "{{ let {name} = {expr}.into_tuple()?; \
+                 if {name}.len() != {len} {{ return Err(::hedera::AbiError::ArgumentCount {{ \
+                 expected: {len}, found: {name}.len() }}); }} \
+                 let mut {name} = {name}.into_iter(); {values} }}",
+                name = name,
+                expr = expr,
+                len = members.len(),
+                values = tuple(&values),
+            )
+        }
+    }
+}
+
+fn to_primitive(expr: &str, int: &str) -> String {
+    format!(
+        "::hedera::abi::ToPrimitive::to_{int}(&{expr})\
+         .ok_or(::hedera::AbiError::Overflow({int:?}))?",
+        int = int,
+        expr = expr
+    )
+}
+
+// `balanceOf` => `balance_of`, `getURI` => `get_uri`; leading underscores are dropped
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.trim_start_matches('_').chars().collect();
+    let mut out = String::new();
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let boundary = match i.checked_sub(1).map(|prev| chars[prev]) {
+                Some(prev) if prev.is_lowercase() || prev.is_numeric() => true,
+                Some(prev) if prev.is_uppercase() => {
+                    chars.get(i + 1).map_or(false, |next| next.is_lowercase())
+                }
+                _ => false,
+            };
+
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(*c);
+        }
+    }
+
+    out
+}
+
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.trim_start_matches('_').chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use failure::Error;
+
+    const ABI: &str = r#"[
+        { "type": "constructor", "inputs": [{ "name": "_supply", "type": "uint256" }] },
+        {
+            "type": "function",
+            "name": "balanceOf",
+            "inputs": [{ "name": "_owner", "type": "address" }],
+            "outputs": [{ "name": "balance", "type": "uint256" }],
+            "stateMutability": "view"
+        },
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [
+                { "name": "to", "type": "address" },
+                { "name": "type", "type": "uint8" }
+            ],
+            "outputs": [{ "name": "", "type": "bool" }, { "name": "", "type": "int24" }]
+        },
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [{ "name": "points", "type": "tuple[]", "components": [
+                { "name": "x", "type": "uint64" },
+                { "name": "y", "type": "string" }
+            ] }],
+            "outputs": []
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "value", "type": "uint256", "indexed": false }
+            ]
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "to", "type": "address", "indexed": true },
+                { "name": "value", "type": "uint256", "indexed": false }
+            ]
+        }
+    ]"#;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(snake_case("balanceOf"), "balance_of");
+        assert_eq!(snake_case("getURI"), "get_uri");
+        assert_eq!(snake_case("ERC20Name"), "erc20_name");
+        assert_eq!(snake_case("_to"), "to");
+        assert_eq!(pascal_case("transfer"), "Transfer");
+
+        assert_eq!(arg_names(vec!["", "type", "client", "x"].into_iter()), vec![
+            "arg0", "type_", "client_", "x"
+        ]);
+    }
+
+    #[test]
+    fn test_rust_types() {
+        assert_eq!(rust_type(&ParamType::Uint(24)), "u32");
+        assert_eq!(rust_type(&ParamType::Int(256)), "::hedera::abi::BigInt");
+        assert_eq!(
+            rust_type(&"(uint8,string)[]".parse().unwrap()),
+            "Vec<(u8, String)>"
+        );
+        assert_eq!(rust_type(&"(bool)".parse().unwrap()), "(bool,)");
+    }
+
+    #[test]
+    fn test_generate() -> Result<(), Error> {
+        let code = Bindings::new("Token", ContractAbi::from_json(ABI)?).generate();
+
+        assert!(code.contains("pub struct Token {"));
+        assert!(code.contains("pub fn constructor_params(supply: ::hedera::abi::BigUint)"));
+        assert!(code.contains("pub fn balance_of_params(owner: ::hedera::SolidityAddress)"));
+        assert!(code.contains("pub fn balance_of_local("));
+        assert!(code.contains("pub fn transfer_params(to: ::hedera::SolidityAddress, type_: u8)"));
+        assert!(code.contains(") -> Result<(bool, i32), ::hedera::AbiError> {"));
+        assert!(code.contains("pub fn transfer_1_params(points: Vec<(u64, String)>)"));
+        assert!(code.contains("pub struct TransferEvent {"));
+        assert!(code.contains("pub struct Transfer1Event {"));
+        assert!(code.contains("pub fn decode_log("));
+        assert!(code.contains("const SIGNATURE: &'static str = \"Transfer(address,uint256)\";"));
+
+        // Braces of the generated items are balanced
+        assert_eq!(code.matches('{').count(), code.matches('}').count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_name_clashes() -> Result<(), Error> {
+        let function = |name: &str| {
+            format!(
+                r#"{{ "type": "function", "name": "{}", "inputs": [], "outputs": [] }}"#,
+                name
+            )
+        };
+
+        let event =
+            |name: &str| format!(r#"{{ "type": "event", "name": "{}", "inputs": [] }}"#, name);
+
+        let abi = format!(
+            r#"[{{ "type": "constructor", "inputs": [] }}, {}]"#,
+            [
+                function("balanceOf"),
+                function("balance_of"),
+                function("f"),
+                function("f"),
+                function("f_1"),
+                function("foo"),
+                function("foo_local"),
+                function("decode_foo"),
+                function("constructor"),
+                event("Transfer"),
+                event("Transfer"),
+                event("Transfer1"),
+                event("Clash"),
+            ]
+            .join(", ")
+        );
+
+        let bindings = Bindings::new("ClashEvent", ContractAbi::from_json(&abi)?);
+
+        let methods: Vec<String> = bindings.functions().into_iter().map(|(_, m)| m).collect();
+        assert_eq!(methods, vec![
+            "balance_of",
+            "balance_of_1",
+            "f",
+            "f_1",
+            "f_1_1",
+            "foo",
+            "foo_local_1",
+            "decode_foo_1",
+            "constructor_1",
+        ]);
+
+        let events: Vec<String> = bindings.events().into_iter().map(|(_, e)| e).collect();
+        assert_eq!(events, vec![
+            "TransferEvent",
+            "Transfer1Event",
+            "Transfer11Event",
+            "Clash1Event"
+        ]);
+
+        // No method of the contract struct is defined twice
+        let code = bindings.generate();
+        let contract = code.split("\n}\n").next().unwrap();
+        let mut names = HashSet::new();
+
+        for line in contract.lines().map(str::trim) {
+            if line.starts_with("pub fn ") {
+                let name = line["pub fn ".len()..].split('(').next().unwrap();
+                assert!(names.insert(name), "duplicate method: {}", name);
+            }
+        }
+
+        Ok(())
+    }
+}
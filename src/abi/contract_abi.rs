@@ -0,0 +1,690 @@
+use crate::{
+    abi::{self, log::keccak256, AbiValue, ContractRevert, ParamType},
+    call_params::CallParams,
+    error::AbiError,
+    function_result::ContractFunctionResult,
+    function_selector::FunctionSelector,
+};
+use serde::Deserialize;
+use std::{io::Read, str::FromStr};
+
+/// A named function, constructor or event parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub kind: ParamType,
+}
+
+/// An event parameter; indexed parameters are stored as log topics rather than log data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventParam {
+    pub name: String,
+    pub kind: ParamType,
+    pub indexed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateMutability {
+    Pure,
+    View,
+    NonPayable,
+    Payable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub inputs: Vec<Param>,
+    pub outputs: Vec<Param>,
+    pub state_mutability: StateMutability,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constructor {
+    pub inputs: Vec<Param>,
+    pub state_mutability: StateMutability,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub name: String,
+    pub inputs: Vec<EventParam>,
+    pub anonymous: bool,
+}
+
+/// A custom error, raised with `revert ErrorName(..)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomError {
+    pub name: String,
+    pub inputs: Vec<Param>,
+}
+
+/// The interface of a Solidity contract, as described by the ABI JSON produced by `solc --abi`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContractAbi {
+    pub constructor: Option<Constructor>,
+    pub functions: Vec<Function>,
+    pub events: Vec<Event>,
+    pub errors: Vec<CustomError>,
+}
+
+impl ContractAbi {
+    pub fn from_json(json: &str) -> Result<Self, AbiError> {
+        Self::from_entries(serde_json::from_str(json)?)
+    }
+
+    pub fn from_reader(reader: impl Read) -> Result<Self, AbiError> {
+        Self::from_entries(serde_json::from_reader(reader)?)
+    }
+
+    // The ABI embedded in another JSON document; older versions of solc write it as a string
+    pub(crate) fn from_value(value: serde_json::Value) -> Result<Self, AbiError> {
+        match value {
+            serde_json::Value::String(json) => Self::from_json(&json),
+            value => Self::from_entries(serde_json::from_value(value)?),
+        }
+    }
+
+    /// The first function named `name`; use `functions_named` to find every overload.
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn functions_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Function> {
+        self.functions.iter().filter(move |f| f.name == name)
+    }
+
+    /// Find the function whose selector is `selector`; the first 4 bytes of its calldata.
+    pub fn function_by_selector(&self, selector: &[u8]) -> Option<&Function> {
+        self.functions.iter().find(|function| selector == &function.selector()[..])
+    }
+
+    /// Find the function called by `data`, by its selector, and decode its parameters; as for
+    /// the function parameters of a `TransactionContractCall`.
+    pub fn decode_call(&self, data: &[u8]) -> Result<(&Function, Vec<AbiValue>), AbiError> {
+        let selector = &data[..data.len().min(4)];
+
+        let function = self
+            .function_by_selector(selector)
+            .ok_or_else(|| AbiError::UnknownSelector(hex::encode(selector)))?;
+
+        Ok((function, function.decode_input(data)?))
+    }
+
+    pub fn event(&self, name: &str) -> Option<&Event> {
+        self.events.iter().find(|e| e.name == name)
+    }
+
+    pub fn error(&self, name: &str) -> Option<&CustomError> {
+        self.errors.iter().find(|e| e.name == name)
+    }
+
+    /// Decode the data of a reverted call as a standard error or one of the custom errors of
+    /// this contract.
+    #[inline]
+    pub fn decode_revert(&self, data: &[u8]) -> ContractRevert {
+        ContractRevert::decode_with(data, self)
+    }
+
+    /// Build the parameters for a call of function `name` with `values`.
+    ///
+    /// Overloads are resolved by choosing the first function named `name` whose inputs
+    /// accept `values`. If none does, the error is that of the last function named `name`.
+    pub fn call_params(&self, name: &str, values: &[AbiValue]) -> Result<CallParams, AbiError> {
+        let mut error = None;
+
+        for function in self.functions_named(name) {
+            match function.call_params(values) {
+                Ok(params) => return Ok(params),
+                Err(e) => error = Some(e),
+            }
+        }
+
+        Err(error.unwrap_or_else(|| AbiError::UnknownFunction(name.to_string())))
+    }
+
+    /// Encode a call of function `name` with `values`; the 4 byte selector followed by the
+    /// encoded parameters.
+    pub fn encode_call(&self, name: &str, values: &[AbiValue]) -> Result<Vec<u8>, AbiError> {
+        Ok(self.call_params(name, values)?.assemble())
+    }
+
+    /// Build the constructor parameters for deploying the contract with `values`.
+    ///
+    /// A contract without a declared constructor takes no parameters.
+    pub fn constructor_params(&self, values: &[AbiValue]) -> Result<CallParams, AbiError> {
+        match &self.constructor {
+            Some(constructor) => constructor.call_params(values),
+
+            None => {
+                check_count(&[], values)?;
+                Ok(CallParams::new(None))
+            }
+        }
+    }
+
+    fn from_entries(entries: Vec<RawEntry>) -> Result<Self, AbiError> {
+        let mut abi = Self::default();
+
+        for entry in entries {
+            let state_mutability = entry.state_mutability();
+
+            match &*entry.kind {
+                "function" => abi.functions.push(Function {
+                    name: entry.name,
+                    inputs: params(entry.inputs)?,
+                    outputs: params(entry.outputs)?,
+                    state_mutability,
+                }),
+
+                "constructor" => {
+                    abi.constructor = Some(Constructor {
+                        inputs: params(entry.inputs)?,
+                        state_mutability,
+                    })
+                }
+
+                "event" => abi.events.push(Event {
+                    name: entry.name,
+                    inputs: entry
+                        .inputs
+                        .into_iter()
+                        .map(|param| {
+                            Ok(EventParam {
+                                indexed: param.indexed,
+                                kind: param.kind()?,
+                                name: param.name,
+                            })
+                        })
+                        .collect::<Result<_, AbiError>>()?,
+                    anonymous: entry.anonymous,
+                }),
+
+                "error" => abi.errors.push(CustomError {
+                    name: entry.name,
+                    inputs: params(entry.inputs)?,
+                }),
+
+                // Fallback and receive functions can not be called by name
+                _ => {}
+            }
+        }
+
+        Ok(abi)
+    }
+}
+
+impl FromStr for ContractAbi {
+    type Err = AbiError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_json(s)
+    }
+}
+
+impl Function {
+    /// Parse a human-readable signature such as `transfer(address,uint256)` into a function
+    /// with unnamed inputs and no outputs.
+    ///
+    /// The parameter types are validated and normalized to their canonical form; for example
+    /// `uint` becomes `uint256`.
+    pub fn parse(signature: &str) -> Result<Self, AbiError> {
+        let invalid = || AbiError::InvalidSignature(signature.to_string());
+        let open = signature.find('(').ok_or_else(invalid)?;
+        let name = signature[..open].trim();
+
+        if !is_identifier(name) {
+            return Err(invalid());
+        }
+
+        let inputs = match signature[open..].parse()? {
+            ParamType::Tuple(members) => members,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Function {
+            name: name.to_string(),
+            inputs: inputs
+                .into_iter()
+                .map(|kind| Param {
+                    name: String::new(),
+                    kind,
+                })
+                .collect(),
+            outputs: Vec::new(),
+            state_mutability: StateMutability::NonPayable,
+        })
+    }
+
+    /// The canonical signature of this function; e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|param| &param.kind))
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        let mut fs = FunctionSelector::new(self.name.clone());
+
+        for param in &self.inputs {
+            fs.add_param_type(param.kind.to_string());
+        }
+
+        let mut selector = [0; 4];
+        selector.copy_from_slice(&fs.finish()[..4]);
+        selector
+    }
+
+    /// Build the parameters for a call of this function with `values`, checking each value
+    /// against the declared type of its input.
+    pub fn call_params(&self, values: &[AbiValue]) -> Result<CallParams, AbiError> {
+        check_count(&self.inputs, values)?;
+
+        let mut params = CallParams::new(Some(self.name.clone()));
+
+        for (param, value) in self.inputs.iter().zip(values) {
+            params.add_value(&param.kind, value)?;
+        }
+
+        Ok(params)
+    }
+
+    /// Encode a call of this function; the 4 byte selector followed by the encoded parameters.
+    pub fn encode_input(&self, values: &[AbiValue]) -> Result<Vec<u8>, AbiError> {
+        Ok(self.call_params(values)?.assemble())
+    }
+
+    /// Decode the parameters of a call of this function from its calldata, including the
+    /// selector.
+    pub fn decode_input(&self, data: &[u8]) -> Result<Vec<AbiValue>, AbiError> {
+        if data.len() < 4 || data[..4] != self.selector() {
+            return Err(AbiError::SelectorMismatch(self.signature()));
+        }
+
+        let types: Vec<ParamType> = self.inputs.iter().map(|param| param.kind.clone()).collect();
+        abi::decode(&types, &data[4..])
+    }
+
+    pub fn decode_output(&self, data: &[u8]) -> Result<Vec<AbiValue>, AbiError> {
+        abi::decode(&self.output_types(), data)
+    }
+
+    pub fn decode_result(
+        &self,
+        result: &ContractFunctionResult,
+    ) -> Result<Vec<AbiValue>, AbiError> {
+        result.decode(&self.output_types())
+    }
+
+    fn output_types(&self) -> Vec<ParamType> {
+        self.outputs.iter().map(|param| param.kind.clone()).collect()
+    }
+}
+
+impl FromStr for Function {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Constructor {
+    pub fn call_params(&self, values: &[AbiValue]) -> Result<CallParams, AbiError> {
+        check_count(&self.inputs, values)?;
+
+        let mut params = CallParams::new(None);
+
+        for (param, value) in self.inputs.iter().zip(values) {
+            params.add_value(&param.kind, value)?;
+        }
+
+        Ok(params)
+    }
+
+    pub fn encode_input(&self, values: &[AbiValue]) -> Result<Vec<u8>, AbiError> {
+        Ok(self.call_params(values)?.assemble())
+    }
+}
+
+impl CustomError {
+    /// The canonical signature of this error; e.g. `InsufficientBalance(uint256,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|param| &param.kind))
+    }
+
+    /// The first 4 bytes of the revert data of this error.
+    pub fn selector(&self) -> [u8; 4] {
+        let mut selector = [0; 4];
+        selector.copy_from_slice(&keccak256(self.signature().as_bytes())[..4]);
+        selector
+    }
+
+    /// Decode the parameters of this error from revert data, including the selector.
+    pub fn decode(&self, data: &[u8]) -> Result<Vec<AbiValue>, AbiError> {
+        if data.len() < 4 || data[..4] != self.selector() {
+            return Err(AbiError::SelectorMismatch(self.signature()));
+        }
+
+        let types: Vec<ParamType> = self.inputs.iter().map(|param| param.kind.clone()).collect();
+        abi::decode(&types, &data[4..])
+    }
+}
+
+impl Event {
+    /// The canonical signature of this event; e.g. `Transfer(address,address,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, self.inputs.iter().map(|param| &param.kind))
+    }
+}
+
+fn signature<'a>(name: &str, types: impl Iterator<Item = &'a ParamType>) -> String {
+    let types: Vec<String> = types.map(ToString::to_string).collect();
+    format!("{}({})", name, types.join(","))
+}
+
+// Solidity identifiers; letters, digits, `_` and `$`, not starting with a digit
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn check_count(inputs: &[Param], values: &[AbiValue]) -> Result<(), AbiError> {
+    if inputs.len() != values.len() {
+        return Err(AbiError::ArgumentCount {
+            expected: inputs.len(),
+            found: values.len(),
+        });
+    }
+
+    Ok(())
+}
+
+fn params(raw: Vec<RawParam>) -> Result<Vec<Param>, AbiError> {
+    raw.into_iter()
+        .map(|param| {
+            Ok(Param {
+                kind: param.kind()?,
+                name: param.name,
+            })
+        })
+        .collect()
+}
+
+// An entry of the ABI JSON, as emitted by solc
+#[derive(Deserialize)]
+struct RawEntry {
+    #[serde(rename = "type", default = "RawEntry::default_kind")]
+    kind: String,
+
+    #[serde(default)]
+    name: String,
+
+    #[serde(default)]
+    inputs: Vec<RawParam>,
+
+    #[serde(default)]
+    outputs: Vec<RawParam>,
+
+    #[serde(rename = "stateMutability", default)]
+    state_mutability: Option<String>,
+
+    // Deprecated in favour of `stateMutability`; still emitted by older compilers
+    #[serde(default)]
+    constant: bool,
+
+    #[serde(default)]
+    payable: bool,
+
+    #[serde(default)]
+    anonymous: bool,
+}
+
+impl RawEntry {
+    fn default_kind() -> String {
+        "function".to_string()
+    }
+
+    fn state_mutability(&self) -> StateMutability {
+        match self.state_mutability.as_ref().map(String::as_str) {
+            Some("pure") => StateMutability::Pure,
+            Some("view") => StateMutability::View,
+            Some("payable") => StateMutability::Payable,
+            Some(_) => StateMutability::NonPayable,
+            None if self.constant => StateMutability::View,
+            None if self.payable => StateMutability::Payable,
+            None => StateMutability::NonPayable,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawParam {
+    #[serde(default)]
+    name: String,
+
+    #[serde(rename = "type")]
+    kind: String,
+
+    #[serde(default)]
+    components: Vec<RawParam>,
+
+    #[serde(default)]
+    indexed: bool,
+}
+
+impl RawParam {
+    // Tuples are declared as `tuple` (with any array suffixes) and their members
+    // as `components`
+    fn kind(&self) -> Result<ParamType, AbiError> {
+        if self.kind.starts_with("tuple") {
+            let members = self
+                .components
+                .iter()
+                .map(RawParam::kind)
+                .collect::<Result<_, _>>()?;
+
+            let suffix = &self.kind["tuple".len()..];
+            return format!("{}{}", ParamType::Tuple(members), suffix).parse();
+        }
+
+        self.kind.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use failure::Error;
+
+    const ABI: &str = r#"[
+        {
+            "type": "constructor",
+            "inputs": [{ "name": "_message", "type": "string" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [
+                { "name": "to", "type": "address" },
+                { "name": "value", "type": "uint" }
+            ],
+            "outputs": [{ "name": "", "type": "bool" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "f",
+            "inputs": [
+                {
+                    "name": "s",
+                    "type": "tuple",
+                    "components": [
+                        { "name": "a", "type": "uint256" },
+                        { "name": "b", "type": "uint256[]" },
+                        {
+                            "name": "c",
+                            "type": "tuple[]",
+                            "components": [
+                                { "name": "x", "type": "uint256" },
+                                { "name": "y", "type": "uint256" }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "name": "t",
+                    "type": "tuple",
+                    "components": [
+                        { "name": "x", "type": "uint256" },
+                        { "name": "y", "type": "uint256" }
+                    ]
+                },
+                { "name": "a", "type": "uint256" }
+            ],
+            "outputs": [],
+            "constant": true
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "to", "type": "address", "indexed": true },
+                { "name": "value", "type": "uint256", "indexed": false }
+            ],
+            "anonymous": false
+        },
+        { "type": "fallback", "stateMutability": "payable" }
+    ]"#;
+
+    #[test]
+    fn test_parse_contract_abi() -> Result<(), Error> {
+        let abi: ContractAbi = ABI.parse()?;
+
+        assert_eq!(abi.functions.len(), 2);
+        assert_eq!(abi.constructor.as_ref().map(|c| c.inputs.len()), Some(1));
+
+        let transfer = abi.function("transfer").unwrap();
+        assert_eq!(transfer.signature(), "transfer(address,uint256)");
+        assert_eq!(hex::encode(transfer.selector()), "a9059cbb");
+        assert_eq!(transfer.state_mutability, StateMutability::NonPayable);
+
+        let f = abi.function("f").unwrap();
+        assert_eq!(
+            f.signature(),
+            "f((uint256,uint256[],(uint256,uint256)[]),(uint256,uint256),uint256)"
+        );
+        assert_eq!(hex::encode(f.selector()), "6f2be728");
+        assert_eq!(f.state_mutability, StateMutability::View);
+
+        let event = abi.event("Transfer").unwrap();
+        assert_eq!(event.signature(), "Transfer(address,address,uint256)");
+        assert!(event.inputs[0].indexed && !event.inputs[2].indexed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_call() -> Result<(), Error> {
+        let abi: ContractAbi = ABI.parse()?;
+
+        let data = abi.encode_call(
+            "transfer",
+            &[
+                AbiValue::Address("00000000000000000000000000000000000003e9".parse()?),
+                AbiValue::Uint(1000u32.into()),
+            ],
+        )?;
+
+        assert_eq!(
+            hex::encode(data),
+            [
+                "a9059cbb",
+                "00000000000000000000000000000000000000000000000000000000000003e9",
+                "00000000000000000000000000000000000000000000000000000000000003e8",
+            ]
+            .concat()
+        );
+
+        let output = abi.function("transfer").unwrap().decode_output(&[
+            0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 1,
+        ])?;
+
+        assert_eq!(output, vec![AbiValue::Bool(true)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_call_rejects_invalid_values() -> Result<(), Error> {
+        let abi: ContractAbi = ABI.parse()?;
+        let transfer = abi.function("transfer").unwrap();
+
+        match transfer.encode_input(&[AbiValue::Bool(true)]) {
+            Err(AbiError::ArgumentCount { expected: 2, found: 1 }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match transfer.encode_input(&[AbiValue::Bool(true), AbiValue::Uint(1u8.into())]) {
+            Err(AbiError::InvalidValue { .. }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // The error of the function, rather than `UnknownFunction`
+        match abi.encode_call("transfer", &[AbiValue::Bool(true), AbiValue::Uint(1u8.into())]) {
+            Err(AbiError::InvalidValue { .. }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match abi.encode_call("approve", &[]) {
+            Err(AbiError::UnknownFunction(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_call() -> Result<(), Error> {
+        let abi: ContractAbi = ABI.parse()?;
+
+        let values = vec![
+            AbiValue::Address("00000000000000000000000000000000000003e9".parse()?),
+            AbiValue::Uint(1000u32.into()),
+        ];
+        let data = abi.encode_call("transfer", &values)?;
+
+        let (function, decoded) = abi.decode_call(&data)?;
+        assert_eq!(function.name, "transfer");
+        assert_eq!(decoded, values);
+
+        match abi.decode_call(&hex::decode("095ea7b3")?) {
+            Err(AbiError::UnknownSelector(selector)) => assert_eq!(selector, "095ea7b3"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_function_signature() -> Result<(), Error> {
+        let function = Function::parse("transfer(address, uint)")?;
+
+        assert_eq!(function.signature(), "transfer(address,uint256)");
+        assert_eq!(hex::encode(function.selector()), "a9059cbb");
+
+        for invalid in &["transfer", "1transfer(address)", "transfer(address)[]", "f(uint7)"] {
+            assert!(Function::parse(invalid).is_err(), "{} should be rejected", invalid);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{
+    abi::{AbiValue, ParamType, WORD_LEN},
+    error::AbiError,
+    solidity_util::SolidityAddress,
+};
+use num::{BigInt, BigUint};
+
+/// Decode ABI encoded `data` as a sequence of values of the given types; as found in
+/// function return data, or in function parameters after the 4 byte selector.
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<AbiValue>, AbiError> {
+    decode_sequence(types.iter(), data, 0)
+}
+
+/// Decode the single value of type `ty` whose head starts at `offset` in `data`.
+///
+/// For a dynamic type, the head holds the offset of the value relative to the start of `data`.
+pub fn decode_at(ty: &ParamType, data: &[u8], offset: usize) -> Result<AbiValue, AbiError> {
+    if ty.is_dynamic() {
+        decode_value(ty, data, read_usize(data, offset)?)
+    } else {
+        decode_value(ty, data, offset)
+    }
+}
+
+// Decode a tuple-like sequence of values laid out as heads followed by tails,
+// where offsets in the heads are relative to `base`
+fn decode_sequence<'a>(
+    types: impl Iterator<Item = &'a ParamType>,
+    data: &[u8],
+    base: usize,
+) -> Result<Vec<AbiValue>, AbiError> {
+    let mut head = base;
+    let mut values = Vec::new();
+
+    for ty in types {
+        if ty.is_dynamic() {
+            let offset = checked_add(base, read_usize(data, head)?)?;
+            values.push(decode_value(ty, data, offset)?);
+        } else {
+            values.push(decode_value(ty, data, head)?);
+        }
+
+        head = checked_add(head, ty.head_len())?;
+    }
+
+    Ok(values)
+}
+
+fn decode_value(ty: &ParamType, data: &[u8], offset: usize) -> Result<AbiValue, AbiError> {
+    Ok(match ty {
+        ParamType::Address => {
+            AbiValue::Address(SolidityAddress::from_slice(&read_word(data, offset)?[12..])?)
+        }
+
+        ParamType::Bool => AbiValue::Bool(read_word(data, offset)?.iter().any(|b| *b != 0)),
+
+        ParamType::Int(_) => AbiValue::Int(BigInt::from_signed_bytes_be(read_word(data, offset)?)),
+
+        ParamType::Uint(_) => AbiValue::Uint(BigUint::from_bytes_be(read_word(data, offset)?)),
+
+        ParamType::FixedBytes(len) => {
+            AbiValue::FixedBytes(read_word(data, offset)?[..(*len).min(WORD_LEN)].to_vec())
+        }
+
+        ParamType::Function => AbiValue::Function(read_word(data, offset)?[..24].to_vec()),
+
+        ParamType::Bytes => AbiValue::Bytes(read_bytes(data, offset)?.to_vec()),
+
+        ParamType::String => {
+            AbiValue::String(String::from_utf8(read_bytes(data, offset)?.to_vec())?)
+        }
+
+        ParamType::Array(inner) => {
+            let len = read_usize(data, offset)?;
+            let start = checked_add(offset, WORD_LEN)?;
+
+            check_array_len(ty, inner, len, data, start)?;
+
+            AbiValue::Array(decode_sequence(
+                std::iter::repeat(&**inner).take(len),
+                data,
+                start,
+            )?)
+        }
+
+        ParamType::FixedArray(inner, len) => {
+            check_array_len(ty, inner, *len, data, offset)?;
+
+            AbiValue::FixedArray(decode_sequence(
+                std::iter::repeat(&**inner).take(*len),
+                data,
+                offset,
+            )?)
+        }
+
+        ParamType::Tuple(members) => {
+            AbiValue::Tuple(decode_sequence(members.iter(), data, offset)?)
+        }
+    })
+}
+
+// Reject array lengths whose heads could not possibly fit in the remaining data, before
+// attempting to decode (and allocate for) them; elements without a head, such as `()`, would
+// take no data at all, so arrays of them are not decoded
+fn check_array_len(
+    ty: &ParamType,
+    inner: &ParamType,
+    len: usize,
+    data: &[u8],
+    start: usize,
+) -> Result<(), AbiError> {
+    if inner.head_len() == 0 {
+        return Err(AbiError::InvalidType(ty.to_string()));
+    }
+
+    let expected = len
+        .checked_mul(inner.head_len())
+        .and_then(|heads| heads.checked_add(start))
+        .ok_or(AbiError::LengthOverflow)?;
+
+    if expected > data.len() {
+        return Err(AbiError::BufferTooShort {
+            expected,
+            found: data.len(),
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn read_word(data: &[u8], offset: usize) -> Result<&[u8], AbiError> {
+    read_slice(data, offset, WORD_LEN)
+}
+
+// Read an offset or a length; these must fit in a `usize` to be usable
+pub(crate) fn read_usize(data: &[u8], offset: usize) -> Result<usize, AbiError> {
+    let word = read_word(data, offset)?;
+
+    if word[..WORD_LEN - 8].iter().any(|b| *b != 0) {
+        return Err(AbiError::LengthOverflow);
+    }
+
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&word[WORD_LEN - 8..]);
+
+    let value = u64::from_be_bytes(bytes);
+
+    if value > usize::max_value() as u64 {
+        return Err(AbiError::LengthOverflow);
+    }
+
+    Ok(value as usize)
+}
+
+// Read the contents of a length-prefixed `bytes` or `string`
+fn read_bytes(data: &[u8], offset: usize) -> Result<&[u8], AbiError> {
+    let len = read_usize(data, offset)?;
+
+    read_slice(data, checked_add(offset, WORD_LEN)?, len)
+}
+
+fn read_slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], AbiError> {
+    let end = checked_add(offset, len)?;
+
+    if end > data.len() {
+        return Err(AbiError::BufferTooShort {
+            expected: end,
+            found: data.len(),
+        });
+    }
+
+    Ok(&data[offset..end])
+}
+
+fn checked_add(a: usize, b: usize) -> Result<usize, AbiError> {
+    a.checked_add(b).ok_or(AbiError::LengthOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use failure::Error;
+
+    fn words(hex_words: &[&str]) -> Vec<u8> {
+        hex::decode(hex_words.concat()).unwrap()
+    }
+
+    #[test]
+    fn test_decode_static() -> Result<(), Error> {
+        let data = words(&[
+            "0000000000000000000000000000000000000000000000000000000000001234",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff00",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "00000000000000000000000000112233445566778899aabbccddeeff00112233",
+            "1122334400000000000000000000000000000000000000000000000000000000",
+        ]);
+
+        let values = decode(
+            &[
+                ParamType::Uint(32),
+                ParamType::Int(16),
+                ParamType::Bool,
+                ParamType::Address,
+                ParamType::FixedBytes(4),
+            ],
+            &data,
+        )?;
+
+        assert_eq!(
+            values,
+            vec![
+                AbiValue::Uint(0x1234u32.into()),
+                AbiValue::Int((-256).into()),
+                AbiValue::Bool(true),
+                AbiValue::Address("00112233445566778899aabbccddeeff00112233".parse()?),
+                AbiValue::FixedBytes(vec![0x11, 0x22, 0x33, 0x44]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_uint256() -> Result<(), Error> {
+        let data = words(&["ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"]);
+        let values = decode(&[ParamType::Uint(256)], &data)?;
+
+        let max = (BigUint::from(1u8) << 256) - BigUint::from(1u8);
+        assert_eq!(values, vec![AbiValue::Uint(max)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_dynamic() -> Result<(), Error> {
+        // f(string, uint256[], bytes3[2]) with ("Hello, world!", [1, 2], ["abc", "def"])
+        let data = words(&[
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "00000000000000000000000000000000000000000000000000000000000000c0",
+            "6162630000000000000000000000000000000000000000000000000000000000",
+            "6465660000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000000000000000000000000000000000000000000d",
+            "48656c6c6f2c20776f726c642100000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+        ]);
+
+        let values = decode(
+            &[
+                ParamType::String,
+                ParamType::Array(Box::new(ParamType::Uint(256))),
+                ParamType::FixedArray(Box::new(ParamType::FixedBytes(3)), 2),
+            ],
+            &data,
+        )?;
+
+        assert_eq!(
+            values,
+            vec![
+                AbiValue::String("Hello, world!".to_string()),
+                AbiValue::Array(vec![
+                    AbiValue::Uint(1u8.into()),
+                    AbiValue::Uint(2u8.into())
+                ]),
+                AbiValue::FixedArray(vec![
+                    AbiValue::FixedBytes(b"abc".to_vec()),
+                    AbiValue::FixedBytes(b"def".to_vec())
+                ]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_nested_dynamic_array() -> Result<(), Error> {
+        // f(string[]) with (["one", "two"])
+        let data = words(&[
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "6f6e650000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "74776f0000000000000000000000000000000000000000000000000000000000",
+        ]);
+
+        let values = decode(&[ParamType::Array(Box::new(ParamType::String))], &data)?;
+
+        assert_eq!(
+            values,
+            vec![AbiValue::Array(vec![
+                AbiValue::String("one".to_string()),
+                AbiValue::String("two".to_string()),
+            ])]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_tuples() -> Result<(), Error> {
+        // f((uint256,bool), (uint256,string)[]) with ((5, true), [(1, "a"), (2, "b")])
+        let data = words(&[
+            "0000000000000000000000000000000000000000000000000000000000000005",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "00000000000000000000000000000000000000000000000000000000000000c0",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "6100000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "6200000000000000000000000000000000000000000000000000000000000000",
+        ]);
+
+        let static_tuple = ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Bool]);
+        let dynamic_tuple = ParamType::Tuple(vec![ParamType::Uint(256), ParamType::String]);
+
+        assert_eq!(static_tuple.to_string(), "(uint256,bool)");
+        assert_eq!(static_tuple.head_len(), 64);
+        assert!(dynamic_tuple.is_dynamic());
+
+        let values = decode(
+            &[static_tuple, ParamType::Array(Box::new(dynamic_tuple))],
+            &data,
+        )?;
+
+        assert_eq!(
+            values,
+            vec![
+                AbiValue::Tuple(vec![AbiValue::Uint(5u8.into()), AbiValue::Bool(true)]),
+                AbiValue::Array(vec![
+                    AbiValue::Tuple(vec![
+                        AbiValue::Uint(1u8.into()),
+                        AbiValue::String("a".to_string())
+                    ]),
+                    AbiValue::Tuple(vec![
+                        AbiValue::Uint(2u8.into()),
+                        AbiValue::String("b".to_string())
+                    ]),
+                ]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_short_buffer() {
+        let data = words(&["0000000000000000000000000000000000000000000000000000000000000020"]);
+
+        assert!(decode(&[ParamType::Bool, ParamType::Bool], &data).is_err());
+        assert!(decode(&[ParamType::Bytes], &data).is_err());
+        assert!(decode(&[ParamType::Uint(8)], &[]).is_err());
+    }
+
+    #[test]
+    fn test_decode_huge_array_length() {
+        let data = words(&[
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "00000000000000000000000000000000000000000000000000ffffffffffffff",
+        ]);
+
+        assert!(decode(&[ParamType::Array(Box::new(ParamType::Bool))], &data).is_err());
+    }
+
+    #[test]
+    fn test_decode_zero_sized_array() {
+        let data = words(&[
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "000000000000000000000000000000000000000000000000ffffffffffffffff",
+        ]);
+
+        let array: ParamType = "()[]".parse().unwrap();
+        let fixed_array = ParamType::FixedArray(Box::new(ParamType::Tuple(Vec::new())), 1 << 40);
+
+        assert!(match decode(&[array], &data) {
+            Err(AbiError::InvalidType(ty)) => ty == "()[]",
+            _ => false,
+        });
+
+        assert!(decode(&[fixed_array], &data).is_err());
+
+        // The heads of huge fixed arrays can not fit either
+        let fixed_array = ParamType::FixedArray(Box::new(ParamType::Bool), usize::max_value());
+        assert!(decode(&[fixed_array], &data).is_err());
+    }
+}
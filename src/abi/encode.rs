@@ -0,0 +1,230 @@
+use crate::{
+    abi::{AbiValue, ParamType, WORD_LEN},
+    call_param_utils::{
+        big_int256, check_fixed_bytes_len, check_int_range, check_int_width, left_pad, right_pad,
+    },
+    error::AbiError,
+};
+use num::BigInt;
+
+/// Encode `values` as a sequence of the given types; as used for function parameters (after
+/// the 4 byte selector) and return data.
+///
+/// Every value is checked against its declared type before it is encoded.
+pub fn encode(types: &[ParamType], values: &[AbiValue]) -> Result<Vec<u8>, AbiError> {
+    if types.len() != values.len() {
+        return Err(AbiError::ArgumentCount {
+            expected: types.len(),
+            found: values.len(),
+        });
+    }
+
+    encode_sequence(types.iter().zip(values))
+}
+
+/// Encode a single value of type `ty`.
+///
+/// Static values are returned as they appear in place; dynamic values as they appear
+/// behind their offset.
+pub fn encode_value(ty: &ParamType, value: &AbiValue) -> Result<Vec<u8>, AbiError> {
+    Ok(match (ty, value) {
+        (ParamType::Address, AbiValue::Address(addr)) => left_pad(addr.to_vec(), false),
+
+        (ParamType::Bool, AbiValue::Bool(b)) => left_pad(vec![u8::from(*b)], false),
+
+        (ParamType::Int(width), AbiValue::Int(_))
+        | (ParamType::Int(width), AbiValue::Uint(_))
+        | (ParamType::Uint(width), AbiValue::Int(_))
+        | (ParamType::Uint(width), AbiValue::Uint(_)) => {
+            let val = match value {
+                AbiValue::Int(val) => val.clone(),
+                AbiValue::Uint(val) => BigInt::from(val.clone()),
+                _ => unreachable!(),
+            };
+
+            let signed = match ty {
+                ParamType::Int(_) => true,
+                _ => false,
+            };
+
+            // `ParamType` is public, so its widths are not known to have been parsed
+            check_int_width(*width)?;
+            check_int_range(&val, *width, signed)?;
+            big_int256(&val)
+        }
+
+        (ParamType::FixedBytes(len), AbiValue::FixedBytes(bytes)) => {
+            check_fixed_bytes_len(*len)?;
+            check_len(ty, bytes.len(), *len)?;
+            right_pad(bytes.clone())
+        }
+
+        (ParamType::Function, AbiValue::Function(bytes)) => {
+            check_len(ty, bytes.len(), 24)?;
+            right_pad(bytes.clone())
+        }
+
+        (ParamType::Bytes, AbiValue::Bytes(bytes)) => encode_bytes(bytes),
+
+        (ParamType::String, AbiValue::String(s)) => encode_bytes(s.as_bytes()),
+
+        (ParamType::Array(inner), AbiValue::Array(values)) => {
+            let mut out = left_pad(values.len().to_be_bytes().to_vec(), false);
+            out.extend(encode_sequence(std::iter::repeat(&**inner).zip(values))?);
+            out
+        }
+
+        (ParamType::FixedArray(inner, len), AbiValue::FixedArray(values)) => {
+            check_len(ty, values.len(), *len)?;
+            encode_sequence(std::iter::repeat(&**inner).zip(values))?
+        }
+
+        (ParamType::Tuple(members), AbiValue::Tuple(values)) => {
+            check_len(ty, values.len(), members.len())?;
+            encode_sequence(members.iter().zip(values))?
+        }
+
+        _ => {
+            return Err(AbiError::InvalidValue {
+                ty: ty.to_string(),
+                reason: format!("found {:?}", value),
+            })
+        }
+    })
+}
+
+// Lay out values as their heads followed by their tails; offsets in the heads are relative
+// to the start of the sequence
+fn encode_sequence<'a>(
+    items: impl Iterator<Item = (&'a ParamType, &'a AbiValue)>,
+) -> Result<Vec<u8>, AbiError> {
+    let mut encoded = Vec::new();
+
+    for (ty, value) in items {
+        encoded.push((ty.is_dynamic(), encode_value(ty, value)?));
+    }
+
+    let mut offset: usize = encoded
+        .iter()
+        .map(|(dynamic, bytes)| if *dynamic { WORD_LEN } else { bytes.len() })
+        .sum();
+
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+
+    for (dynamic, bytes) in encoded {
+        if dynamic {
+            head.extend(left_pad(offset.to_be_bytes().to_vec(), false));
+            offset += bytes.len();
+            tail.extend(bytes);
+        } else {
+            head.extend(bytes);
+        }
+    }
+
+    head.extend(tail);
+    Ok(head)
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut out = left_pad(bytes.len().to_be_bytes().to_vec(), false);
+    out.extend(right_pad(bytes.to_vec()));
+    out
+}
+
+fn check_len(ty: &ParamType, found: usize, expected: usize) -> Result<(), AbiError> {
+    if found != expected {
+        return Err(AbiError::InvalidValue {
+            ty: ty.to_string(),
+            reason: format!("expected a length of {}; found {}", expected, found),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::decode;
+    use failure::Error;
+
+    #[test]
+    fn test_encode_round_trip() -> Result<(), Error> {
+        let types = vec![
+            ParamType::Uint(256),
+            ParamType::String,
+            ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::Int(8),
+                ParamType::Bytes,
+            ]))),
+            ParamType::FixedArray(Box::new(ParamType::FixedBytes(3)), 2),
+            ParamType::Address,
+        ];
+
+        let values = vec![
+            AbiValue::Uint(1_000_000u32.into()),
+            AbiValue::String("Hello, world!".to_string()),
+            AbiValue::Array(vec![
+                AbiValue::Tuple(vec![AbiValue::Int((-1).into()), AbiValue::Bytes(vec![1, 2])]),
+                AbiValue::Tuple(vec![AbiValue::Int(127.into()), AbiValue::Bytes(vec![])]),
+            ]),
+            AbiValue::FixedArray(vec![
+                AbiValue::FixedBytes(b"abc".to_vec()),
+                AbiValue::FixedBytes(b"def".to_vec()),
+            ]),
+            AbiValue::Address([0x11; 20].into()),
+        ];
+
+        let data = encode(&types, &values)?;
+
+        assert_eq!(data.len() % WORD_LEN, 0);
+        assert_eq!(decode(&types, &data)?, values);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_invalid_values() {
+        let invalid = vec![
+            (ParamType::Uint(8), AbiValue::Uint(256u32.into())),
+            (ParamType::Uint(256), AbiValue::Int((-1).into())),
+            (ParamType::Int(8), AbiValue::Int((-129).into())),
+            (ParamType::FixedBytes(4), AbiValue::FixedBytes(vec![0; 5])),
+            (ParamType::Bool, AbiValue::String("true".to_string())),
+            (
+                ParamType::FixedArray(Box::new(ParamType::Bool), 2),
+                AbiValue::FixedArray(vec![AbiValue::Bool(true)]),
+            ),
+        ];
+
+        for (ty, value) in invalid {
+            match encode_value(&ty, &value) {
+                Err(AbiError::InvalidValue { .. }) => {}
+                result => panic!(
+                    "expected {:?} to be rejected for {}; got {:?}", value, ty, result
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_invalid_types() {
+        let zero = || AbiValue::Uint(0u8.into());
+
+        assert!(match encode_value(&ParamType::Int(0), &zero()) {
+            Err(AbiError::IntWidth(0)) => true,
+            _ => false,
+        });
+
+        assert!(match encode_value(&ParamType::Uint(4096), &zero()) {
+            Err(AbiError::IntWidth(4096)) => true,
+            _ => false,
+        });
+
+        assert!(match encode_value(&ParamType::FixedBytes(0), &AbiValue::FixedBytes(vec![])) {
+            Err(AbiError::InvalidType(ty)) => ty == "bytes0",
+            _ => false,
+        });
+    }
+}
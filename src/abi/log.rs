@@ -0,0 +1,265 @@
+use crate::{
+    abi::{self, AbiValue, ContractAbi, Event, ParamType, WORD_LEN},
+    error::AbiError,
+    function_result::ContractLogInfo,
+};
+use sha3::{Digest, Keccak256};
+
+/// Length of a log bloom filter; 2048 bits.
+pub const BLOOM_LEN: usize = 256;
+
+impl Event {
+    /// The first topic of every log of this event; the keccak hash of its signature.
+    pub fn topic(&self) -> [u8; 32] {
+        keccak256(self.signature().as_bytes())
+    }
+
+    /// Decode the parameters of this event from a log, in declaration order.
+    ///
+    /// Indexed parameters of a dynamic type, a tuple or an array are only stored as the
+    /// keccak hash of their encoding; those are returned as a `bytes32`.
+    pub fn decode_log(&self, log: &ContractLogInfo) -> Result<Vec<AbiValue>, AbiError> {
+        self.decode(&log.topic, &log.data)
+    }
+
+    pub fn decode(&self, topics: &[Vec<u8>], data: &[u8]) -> Result<Vec<AbiValue>, AbiError> {
+        let mut topics = topics.iter();
+
+        if !self.anonymous {
+            match topics.next() {
+                Some(topic) if topic[..] == self.topic()[..] => {}
+                _ => return Err(AbiError::TopicMismatch(self.signature())),
+            }
+        }
+
+        let indexed = self.inputs.iter().filter(|param| param.indexed).count();
+
+        if topics.len() != indexed {
+            return Err(AbiError::ArgumentCount {
+                expected: indexed,
+                found: topics.len(),
+            });
+        }
+
+        let types: Vec<ParamType> = self
+            .inputs
+            .iter()
+            .filter(|param| !param.indexed)
+            .map(|param| param.kind.clone())
+            .collect();
+
+        let mut values = abi::decode(&types, data)?.into_iter();
+
+        self.inputs
+            .iter()
+            .map(|param| {
+                if param.indexed {
+                    decode_topic(&param.kind, topics.next().unwrap())
+                } else {
+                    Ok(values.next().unwrap())
+                }
+            })
+            .collect()
+    }
+}
+
+impl ContractAbi {
+    /// Find the event a log was emitted for, by its first topic, and decode its parameters.
+    pub fn decode_log(&self, log: &ContractLogInfo) -> Result<(&Event, Vec<AbiValue>), AbiError> {
+        let topic = log.topic.first().map(Vec::as_slice).unwrap_or_default();
+
+        let event = self
+            .events
+            .iter()
+            .find(|event| !event.anonymous && topic == &event.topic()[..])
+            .ok_or_else(|| AbiError::UnknownEvent(hex::encode(topic)))?;
+
+        Ok((event, event.decode_log(log)?))
+    }
+}
+
+/// Check whether `input` (a contract address or a log topic) might have been added to a log
+/// bloom filter.
+///
+/// A `false` result is definite; a `true` result may be a false positive. An empty bloom filter
+/// contains nothing, while one that is not `BLOOM_LEN` bytes long can not rule anything out.
+pub fn bloom_contains(bloom: &[u8], input: &[u8]) -> bool {
+    if bloom.is_empty() {
+        return false;
+    }
+
+    if bloom.len() != BLOOM_LEN {
+        return true;
+    }
+
+    bloom_bits(input)
+        .iter()
+        .all(|(byte, mask)| bloom[*byte] & mask != 0)
+}
+
+// Add `input` to a bloom filter; only tests build bloom filters
+#[cfg(test)]
+fn bloom_insert(bloom: &mut [u8; BLOOM_LEN], input: &[u8]) {
+    for (byte, mask) in &bloom_bits(input) {
+        bloom[*byte] |= mask;
+    }
+}
+
+// The 3 bits set for `input`; each is taken from a pair of bytes of its keccak hash,
+// modulo 2048, counting from the last bit of the filter
+fn bloom_bits(input: &[u8]) -> [(usize, u8); 3] {
+    let hash = keccak256(input);
+    let mut bits = [(0, 0); 3];
+
+    for (i, bit) in bits.iter_mut().enumerate() {
+        let index = ((usize::from(hash[2 * i]) << 8) | usize::from(hash[2 * i + 1])) & 0x7ff;
+        *bit = (BLOOM_LEN - 1 - index / 8, 1 << (index % 8));
+    }
+
+    bits
+}
+
+// Values of a type that fits in a word are stored in the topic as they would be encoded;
+// anything else is stored as the hash of its encoding
+fn decode_topic(ty: &ParamType, topic: &[u8]) -> Result<AbiValue, AbiError> {
+    match ty {
+        ParamType::Address
+        | ParamType::Bool
+        | ParamType::Int(_)
+        | ParamType::Uint(_)
+        | ParamType::FixedBytes(_)
+        | ParamType::Function => abi::decode_at(ty, topic, 0),
+
+        _ => Ok(AbiValue::FixedBytes(super::decode::read_word(topic, 0)?.to_vec())),
+    }
+}
+
+pub(crate) fn keccak256(input: &[u8]) -> [u8; WORD_LEN] {
+    let mut hasher = Keccak256::default();
+    hasher.input(input);
+
+    let mut hash = [0; WORD_LEN];
+    hash.copy_from_slice(&hasher.result());
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractId;
+    use failure::Error;
+
+    const ABI: &str = r#"[
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "to", "type": "address", "indexed": true },
+                { "name": "value", "type": "uint256", "indexed": false }
+            ]
+        },
+        {
+            "type": "event",
+            "name": "Message",
+            "inputs": [
+                { "name": "tag", "type": "string", "indexed": true },
+                { "name": "text", "type": "string", "indexed": false }
+            ]
+        }
+    ]"#;
+
+    fn log(topic: Vec<Vec<u8>>, data: Vec<u8>) -> ContractLogInfo {
+        ContractLogInfo {
+            contract_id: ContractId {
+                shard: 0,
+                realm: 0,
+                contract: 1001,
+            },
+            bloom: Vec::new(),
+            topic,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_event_topic() -> Result<(), Error> {
+        let abi = ContractAbi::from_json(ABI)?;
+
+        assert_eq!(
+            hex::encode(abi.event("Transfer").unwrap().topic()),
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_log() -> Result<(), Error> {
+        let abi = ContractAbi::from_json(ABI)?;
+        let transfer = abi.event("Transfer").unwrap();
+
+        let word = |hex: &str| hex::decode(format!("{:0>64}", hex));
+
+        let log = log(
+            vec![transfer.topic().to_vec(), word("3e9")?, word("3ea")?],
+            word("64")?,
+        );
+        let (event, values) = abi.decode_log(&log)?;
+
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(
+            values,
+            vec![
+                AbiValue::Address("00000000000000000000000000000000000003e9".parse()?),
+                AbiValue::Address("00000000000000000000000000000000000003ea".parse()?),
+                AbiValue::Uint(100u8.into()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_log_hashed_topic() -> Result<(), Error> {
+        let abi = ContractAbi::from_json(ABI)?;
+        let message = abi.event("Message").unwrap();
+
+        let data = abi::encode(&[ParamType::String], &[AbiValue::String("hello".to_string())])?;
+        let tag = keccak256(b"greeting");
+
+        let values = message.decode_log(&log(vec![message.topic().to_vec(), tag.to_vec()], data))?;
+
+        assert_eq!(
+            values,
+            vec![
+                AbiValue::FixedBytes(tag.to_vec()),
+                AbiValue::String("hello".to_string())
+            ]
+        );
+
+        // The first topic must be the event signature
+        match message.decode_log(&log(vec![tag.to_vec(), tag.to_vec()], Vec::new())) {
+            Err(AbiError::TopicMismatch(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bloom_contains() {
+        let address = hex::decode("00000000000000000000000000000000000003e9").unwrap();
+        let topic = keccak256(b"Transfer(address,address,uint256)");
+
+        let mut bloom = [0; BLOOM_LEN];
+        bloom_insert(&mut bloom, &address);
+        bloom_insert(&mut bloom, &topic);
+
+        assert_eq!(bloom.iter().map(|b| b.count_ones()).sum::<u32>(), 6);
+        assert!(bloom_contains(&bloom, &address));
+        assert!(bloom_contains(&bloom, &topic));
+        assert!(!bloom_contains(&bloom, &keccak256(b"Approval(address,address,uint256)")));
+        assert!(!bloom_contains(&[], &address));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{abi::WORD_LEN, error::AbiError};
+use std::{fmt, str::FromStr};
+
+/// The type of a Solidity function parameter or return value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamType {
+    Address,
+    Bool,
+    /// A signed integer of the given width in bits; `int8` through `int256`.
+    Int(usize),
+    /// An unsigned integer of the given width in bits; `uint8` through `uint256`.
+    Uint(usize),
+    /// `bytes1` through `bytes32`.
+    FixedBytes(usize),
+    Bytes,
+    String,
+    /// An address followed by a function selector.
+    Function,
+    /// A dynamically sized array; `T[]`.
+    Array(Box<ParamType>),
+    /// A fixed size array; `T[N]`.
+    FixedArray(Box<ParamType>, usize),
+    /// A tuple (a Solidity `struct`) with members of the given types.
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// Returns `true` if values of this type are encoded out-of-line, behind an offset.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => true,
+            ParamType::FixedArray(inner, _) => inner.is_dynamic(),
+            ParamType::Tuple(members) => members.iter().any(ParamType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// The number of bytes a value of this type occupies in the head of an encoding.
+    ///
+    /// Saturates, so that lengths of huge fixed arrays fail the bounds checks of decoding.
+    pub(crate) fn head_len(&self) -> usize {
+        match self {
+            ParamType::FixedArray(inner, len) if !inner.is_dynamic() => {
+                inner.head_len().saturating_mul(*len)
+            }
+            ParamType::Tuple(members) if !self.is_dynamic() => members
+                .iter()
+                .map(ParamType::head_len)
+                .fold(0, usize::saturating_add),
+            _ => WORD_LEN,
+        }
+    }
+}
+
+/// Format a `ParamType` as its canonical name, as used in function signatures.
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamType::Address => f.write_str("address"),
+            ParamType::Bool => f.write_str("bool"),
+            ParamType::Int(width) => write!(f, "int{}", width),
+            ParamType::Uint(width) => write!(f, "uint{}", width),
+            ParamType::FixedBytes(len) => write!(f, "bytes{}", len),
+            ParamType::Bytes => f.write_str("bytes"),
+            ParamType::String => f.write_str("string"),
+            ParamType::Function => f.write_str("function"),
+            ParamType::Array(inner) => write!(f, "{}[]", inner),
+            ParamType::FixedArray(inner, len) => write!(f, "{}[{}]", inner, len),
+            ParamType::Tuple(members) => {
+                f.write_str("(")?;
+
+                for (i, member) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+
+                    write!(f, "{}", member)?;
+                }
+
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// Parse a Solidity type name, as found in ABI JSON or function signatures.
+///
+/// The aliases `uint` and `int` are normalized to `uint256` and `int256`; tuples are written
+/// as their member types in parentheses; e.g. `(uint256,string)[]`.
+impl FromStr for ParamType {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AbiError::InvalidType(s.to_string());
+        let s = s.trim();
+
+        // Array suffixes bind last; `T[2][]` is a dynamic array of `T[2]`
+        if s.ends_with(']') {
+            let open = s.rfind('[').ok_or_else(invalid)?;
+            let inner = Box::new(s[..open].parse()?);
+            let len = &s[open + 1..s.len() - 1];
+
+            return if len.is_empty() {
+                Ok(ParamType::Array(inner))
+            } else {
+                Ok(ParamType::FixedArray(inner, len.parse().map_err(|_| invalid())?))
+            };
+        }
+
+        if s.starts_with('(') && s.ends_with(')') {
+            let members = &s[1..s.len() - 1];
+
+            return split_members(members)
+                .ok_or_else(invalid)?
+                .into_iter()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map(ParamType::Tuple);
+        }
+
+        Ok(match s {
+            "address" => ParamType::Address,
+            "bool" => ParamType::Bool,
+            "bytes" => ParamType::Bytes,
+            "string" => ParamType::String,
+            "function" => ParamType::Function,
+            "int" => ParamType::Int(256),
+            "uint" => ParamType::Uint(256),
+
+            _ if s.starts_with("uint") => {
+                ParamType::Uint(parse_width(&s[4..], 8, 256).ok_or_else(invalid)?)
+            }
+
+            _ if s.starts_with("int") => {
+                ParamType::Int(parse_width(&s[3..], 8, 256).ok_or_else(invalid)?)
+            }
+
+            _ if s.starts_with("bytes") => {
+                ParamType::FixedBytes(parse_width(&s[5..], 1, WORD_LEN).ok_or_else(invalid)?)
+            }
+
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+// Parse the size suffix of an integer width in bits or a fixed bytes length; these are
+// multiples of `step` and never written with leading zeros
+fn parse_width(width: &str, step: usize, max: usize) -> Option<usize> {
+    if width.starts_with('0') {
+        return None;
+    }
+
+    match width.parse() {
+        Ok(width) if width % step == 0 && width >= step && width <= max => Some(width),
+        _ => None,
+    }
+}
+
+// Split the member list of a tuple at the commas that are not nested in another tuple
+fn split_members(members: &str) -> Option<Vec<&str>> {
+    if members.trim().is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut split = Vec::new();
+
+    for (i, c) in members.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+
+            ',' if depth == 0 => {
+                split.push(&members[start..i]);
+                start = i + 1;
+            }
+
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+
+    split.push(&members[start..]);
+    Some(split)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_param_type() -> Result<(), AbiError> {
+        let cases = vec![
+            ("uint", "uint256"),
+            ("int", "int256"),
+            ("uint8", "uint8"),
+            ("bytes32", "bytes32"),
+            ("address[]", "address[]"),
+            ("uint[2][]", "uint256[2][]"),
+            ("(uint,(bool,string)[],bytes3[2])[4]", "(uint256,(bool,string)[],bytes3[2])[4]"),
+            ("()", "()"),
+        ];
+
+        for (name, canonical) in cases {
+            assert_eq!(name.parse::<ParamType>()?.to_string(), canonical);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid_param_type() {
+        let cases = vec![
+            "uint7", "uint264", "uint08", "bytes0", "bytes33", "tuple", "(uint,bool", "uint]",
+            "(bool))", "fixed128x18", "",
+        ];
+
+        for name in cases {
+            assert!(name.parse::<ParamType>().is_err(), "{} should be rejected", name);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::abi::{self, AbiValue, ContractAbi, ParamType};
+use num::{BigUint, ToPrimitive};
+use std::fmt;
+
+/// Selector of `Error(string)`; used by `revert("reason")` and `require(condition, "reason")`.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`; used by failed assertions and checked arithmetic.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// The reason a contract call reverted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractRevert {
+    /// A revert with a reason string; `Error(string)`.
+    Error(String),
+
+    /// A panic with one of the codes of the Solidity compiler; `Panic(uint256)`.
+    Panic(BigUint),
+
+    /// A custom error declared in the contract ABI, with its decoded parameters.
+    Custom { name: String, values: Vec<AbiValue> },
+
+    /// Revert data that could not be decoded; empty for a revert without a reason.
+    Raw(Vec<u8>),
+
+    /// An error message from the network that did not contain any revert data.
+    Message(String),
+}
+
+impl ContractRevert {
+    /// Decode revert data as a standard `Error(string)` or `Panic(uint256)`.
+    pub fn decode(data: &[u8]) -> Self {
+        decode_standard(data).unwrap_or_else(|| ContractRevert::Raw(data.to_vec()))
+    }
+
+    /// Decode revert data as a standard error or as one of the custom errors of `abi`.
+    pub fn decode_with(data: &[u8], abi: &ContractAbi) -> Self {
+        if let Some(revert) = decode_standard(data) {
+            return revert;
+        }
+
+        abi.errors
+            .iter()
+            .filter(|error| data.len() >= 4 && data[..4] == error.selector())
+            .find_map(|error| {
+                Some(ContractRevert::Custom {
+                    name: error.name.clone(),
+                    values: error.decode(data).ok()?,
+                })
+            })
+            .unwrap_or_else(|| ContractRevert::Raw(data.to_vec()))
+    }
+
+    /// A description of a panic code, if it is one the Solidity compiler uses.
+    pub fn panic_description(code: &BigUint) -> Option<&'static str> {
+        Some(match code.to_u8()? {
+            0x00 => "generic compiler panic",
+            0x01 => "assertion failed",
+            0x11 => "arithmetic overflow or underflow",
+            0x12 => "division or modulo by zero",
+            0x21 => "conversion into an invalid enum value",
+            0x22 => "access to an incorrectly encoded storage byte array",
+            0x31 => "pop from an empty array",
+            0x32 => "array index out of bounds",
+            0x41 => "out of memory",
+            0x51 => "call to an uninitialized internal function",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for ContractRevert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContractRevert::Error(reason) => write!(f, "reverted with reason: {}", reason),
+
+            ContractRevert::Panic(code) => match ContractRevert::panic_description(code) {
+                Some(description) => write!(f, "panicked with code {:#x}: {}", code, description),
+                None => write!(f, "panicked with code {:#x}", code),
+            },
+
+            ContractRevert::Custom { name, values } => {
+                write!(f, "reverted with error {}{:?}", name, values)
+            }
+
+            ContractRevert::Raw(data) if data.is_empty() => {
+                f.write_str("reverted without a reason")
+            }
+
+            ContractRevert::Raw(data) => write!(f, "reverted with data 0x{}", hex::encode(data)),
+
+            ContractRevert::Message(message) => f.write_str(message),
+        }
+    }
+}
+
+fn decode_standard(data: &[u8]) -> Option<ContractRevert> {
+    if data.len() < 4 {
+        return None;
+    }
+
+    let (selector, params) = data.split_at(4);
+
+    if selector == ERROR_SELECTOR {
+        match abi::decode(&[ParamType::String], params).ok()?.pop()? {
+            AbiValue::String(reason) => Some(ContractRevert::Error(reason)),
+            _ => None,
+        }
+    } else if selector == PANIC_SELECTOR {
+        match abi::decode(&[ParamType::Uint(256)], params).ok()?.pop()? {
+            AbiValue::Uint(code) => Some(ContractRevert::Panic(code)),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use failure::Error;
+
+    #[test]
+    fn test_decode_error() -> Result<(), Error> {
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend(abi::encode(
+            &[ParamType::String],
+            &[AbiValue::String("Not enough Ether provided.".to_string())],
+        )?);
+
+        let revert = ContractRevert::decode(&data);
+
+        assert_eq!(revert, ContractRevert::Error("Not enough Ether provided.".to_string()));
+        assert_eq!(revert.to_string(), "reverted with reason: Not enough Ether provided.");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_panic() -> Result<(), Error> {
+        let data = hex::decode(
+            "4e487b710000000000000000000000000000000000000000000000000000000000000011",
+        )?;
+
+        let revert = ContractRevert::decode(&data);
+
+        assert_eq!(revert, ContractRevert::Panic(0x11u8.into()));
+        assert_eq!(revert.to_string(), "panicked with code 0x11: arithmetic overflow or underflow");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_custom() -> Result<(), Error> {
+        let abi = ContractAbi::from_json(
+            r#"[{
+                "type": "error",
+                "name": "InsufficientBalance",
+                "inputs": [
+                    { "name": "available", "type": "uint256" },
+                    { "name": "required", "type": "uint256" }
+                ]
+            }]"#,
+        )?;
+
+        let error = &abi.errors[0];
+        assert_eq!(error.signature(), "InsufficientBalance(uint256,uint256)");
+
+        let mut data = error.selector().to_vec();
+        data.extend(abi::encode(
+            &[ParamType::Uint(256), ParamType::Uint(256)],
+            &[AbiValue::Uint(5u8.into()), AbiValue::Uint(10u8.into())],
+        )?);
+
+        assert_eq!(
+            ContractRevert::decode_with(&data, &abi),
+            ContractRevert::Custom {
+                name: "InsufficientBalance".to_string(),
+                values: vec![AbiValue::Uint(5u8.into()), AbiValue::Uint(10u8.into())],
+            }
+        );
+
+        // Without the ABI the error is unknown
+        assert_eq!(ContractRevert::decode(&data), ContractRevert::Raw(data.clone()));
+        assert_eq!(ContractRevert::decode(&[]).to_string(), "reverted without a reason");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{error::AbiError, solidity_util::SolidityAddress};
+use num::{BigInt, BigUint};
+
+/// A decoded Solidity value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbiValue {
+    Address(SolidityAddress),
+    Bool(bool),
+    Int(BigInt),
+    Uint(BigUint),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    /// The 20 bytes of an address followed by a 4 byte function selector.
+    Function(Vec<u8>),
+    Array(Vec<AbiValue>),
+    FixedArray(Vec<AbiValue>),
+    Tuple(Vec<AbiValue>),
+}
+
+impl AbiValue {
+    pub fn into_address(self) -> Result<SolidityAddress, AbiError> {
+        match self {
+            AbiValue::Address(addr) => Ok(addr),
+            value => Err(value.mismatch("address")),
+        }
+    }
+
+    pub fn into_bool(self) -> Result<bool, AbiError> {
+        match self {
+            AbiValue::Bool(b) => Ok(b),
+            value => Err(value.mismatch("bool")),
+        }
+    }
+
+    pub fn into_int(self) -> Result<BigInt, AbiError> {
+        match self {
+            AbiValue::Int(val) => Ok(val),
+            value => Err(value.mismatch("int")),
+        }
+    }
+
+    pub fn into_uint(self) -> Result<BigUint, AbiError> {
+        match self {
+            AbiValue::Uint(val) => Ok(val),
+            value => Err(value.mismatch("uint")),
+        }
+    }
+
+    /// The contents of a `bytes`, `bytesN` or `function`.
+    pub fn into_bytes(self) -> Result<Vec<u8>, AbiError> {
+        match self {
+            AbiValue::Bytes(bytes) | AbiValue::FixedBytes(bytes) | AbiValue::Function(bytes) => {
+                Ok(bytes)
+            }
+
+            value => Err(value.mismatch("bytes")),
+        }
+    }
+
+    pub fn into_string(self) -> Result<String, AbiError> {
+        match self {
+            AbiValue::String(s) => Ok(s),
+            value => Err(value.mismatch("string")),
+        }
+    }
+
+    /// The elements of a dynamic or fixed size array.
+    pub fn into_array(self) -> Result<Vec<AbiValue>, AbiError> {
+        match self {
+            AbiValue::Array(values) | AbiValue::FixedArray(values) => Ok(values),
+            value => Err(value.mismatch("array")),
+        }
+    }
+
+    pub fn into_tuple(self) -> Result<Vec<AbiValue>, AbiError> {
+        match self {
+            AbiValue::Tuple(values) => Ok(values),
+            value => Err(value.mismatch("tuple")),
+        }
+    }
+
+    fn mismatch(&self, expected: &str) -> AbiError {
+        AbiError::InvalidValue {
+            ty: expected.to_string(),
+            reason: format!("found {:?}", self),
+        }
+    }
+}
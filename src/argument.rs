@@ -1,3 +1,4 @@
+use crate::error::AbiError;
 
 #[derive(Clone)]
 pub(crate) struct Argument {
@@ -6,14 +7,15 @@ pub(crate) struct Argument {
 }
 
 impl Argument {
-    pub(crate) fn new(val: Vec<u8>, dynam: bool) -> Self {
-        if dynam == false && val.len() != 32 {
-            panic!("ILLEGAL ARGUMENT ERROR: value argument that was not 32 bytes; value was \
-            {:#?} bytes", val.len());
+    pub(crate) fn new(val: Vec<u8>, dynam: bool) -> Result<Self, AbiError> {
+        // Static values are encoded in place; tuples and fixed arrays of static values can take
+        // up more than one 32-byte word
+        if dynam == false && (val.is_empty() || val.len() % 32 != 0) {
+            return Err(AbiError::UnalignedValue(val.len()));
         }
-        Self {
+        Ok(Self {
             value: val,
             dynamic: dynam
-        }
+        })
     }
 }
@@ -0,0 +1,293 @@
+// Compiled contracts, as loaded from the output of solc, Truffle or Hardhat
+use crate::{
+    abi::{AbiValue, ContractAbi},
+    bytecode::{Bytecode, RawBytecode, RawLinkReferences},
+    call_params::CallParams,
+    error::AbiError,
+    Client, ContractDeploy, ContractId, SolidityAddress,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use try_from::TryFrom;
+
+/// A compiled contract; its ABI, and its creation and runtime bytecode.
+///
+/// Libraries must be linked with `link` before the contract can be deployed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledContract {
+    /// The fully qualified name of the contract (`file:Name`) if known, or just its name.
+    pub name: String,
+
+    pub abi: ContractAbi,
+
+    /// The bytecode that creates the contract; the constructor and the runtime bytecode.
+    pub bytecode: Bytecode,
+
+    /// The bytecode of the contract once deployed; see `Bytecode::compare`.
+    pub deployed_bytecode: Bytecode,
+}
+
+impl CompiledContract {
+    /// Load a Truffle or Hardhat artifact.
+    pub fn from_artifact_json(json: &str) -> Result<Self, AbiError> {
+        let raw: RawArtifact = serde_json::from_str(json)?;
+
+        let name = match raw.source_name {
+            Some(source) => format!("{}:{}", source, raw.contract_name),
+            None => raw.contract_name,
+        };
+
+        // Truffle leaves out the link references; the placeholders hold the library names
+        let bytecode = |object, link_references| {
+            Bytecode::from_raw(RawBytecode {
+                object,
+                link_references,
+                immutable_references: BTreeMap::new(),
+            })
+        };
+
+        Ok(Self {
+            name,
+            abi: ContractAbi::from_value(raw.abi)?,
+            bytecode: bytecode(raw.bytecode, raw.link_references)?,
+            deployed_bytecode: bytecode(raw.deployed_bytecode, raw.deployed_link_references)?,
+        })
+    }
+
+    /// Load contract `name` from the output of `solc --combined-json abi,bin,bin-runtime`.
+    ///
+    /// `name` is either the fully qualified name of the contract (`file:Name`), or just its name
+    /// if only one contract of the output has it.
+    pub fn from_combined_json(json: &str, name: &str) -> Result<Self, AbiError> {
+        let mut contracts = Self::all_from_combined_json(json)?;
+
+        if let Some(i) = contracts.iter().position(|contract| contract.name == name) {
+            return Ok(contracts.swap_remove(i));
+        }
+
+        let mut named = contracts
+            .into_iter()
+            .filter(|contract| contract.name.rsplit(':').next() == Some(name));
+
+        match (named.next(), named.next()) {
+            (Some(contract), None) => Ok(contract),
+            _ => Err(AbiError::UnknownContract(name.to_string())),
+        }
+    }
+
+    /// Load every contract from the output of `solc --combined-json abi,bin,bin-runtime`.
+    pub fn all_from_combined_json(json: &str) -> Result<Vec<Self>, AbiError> {
+        let raw: RawCombinedJson = serde_json::from_str(json)?;
+
+        raw.contracts
+            .into_iter()
+            .map(|(name, contract)| {
+                Ok(Self {
+                    name,
+                    abi: ContractAbi::from_value(contract.abi)?,
+                    bytecode: Bytecode::from_hex(&contract.bin)?,
+                    deployed_bytecode: Bytecode::from_hex(&contract.bin_runtime)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Link the library `library` deployed as `id`; see `Bytecode::link`.
+    pub fn link(&mut self, library: &str, id: ContractId) -> Result<&mut Self, AbiError> {
+        let address = SolidityAddress::try_from(id)?;
+
+        let linked = self.bytecode.link(library, address);
+        let deployed_linked = self.deployed_bytecode.link(library, address);
+
+        if !linked && !deployed_linked {
+            return Err(AbiError::UnknownLibrary(library.to_string()));
+        }
+
+        Ok(self)
+    }
+
+    /// The creation bytecode; fails if any library has not been linked.
+    pub fn creation_code(&self) -> Result<&[u8], AbiError> {
+        match self.bytecode.unlinked_libraries().first() {
+            Some(library) => Err(AbiError::UnlinkedLibrary(library.to_string())),
+            None => Ok(&self.bytecode.code),
+        }
+    }
+
+    /// The contents of the file to create the contract from with `TransactionContractCreate`;
+    /// the hex-encoded creation bytecode.
+    pub fn file_contents(&self) -> Result<Vec<u8>, AbiError> {
+        Ok(hex::encode(self.creation_code()?).into_bytes())
+    }
+
+    /// Build the constructor parameters for creating the contract with `values`; see
+    /// `ContractAbi::constructor_params`.
+    pub fn constructor_params(&self, values: &[AbiValue]) -> Result<CallParams, AbiError> {
+        self.abi.constructor_params(values)
+    }
+
+    /// Build the parameters for a call of function `name` with `values`; see
+    /// `ContractAbi::call_params`.
+    pub fn call_params(&self, name: &str, values: &[AbiValue]) -> Result<CallParams, AbiError> {
+        self.abi.call_params(name, values)
+    }
+
+    /// Deploy the contract, constructed with `values`; see `Client::deploy_contract`.
+    pub fn deploy<'a>(
+        &self,
+        client: &'a Client,
+        values: &[AbiValue],
+    ) -> Result<ContractDeploy<'a>, AbiError> {
+        let constructor_parameters = self.constructor_params(values)?;
+
+        Ok(client.deploy_contract(self.creation_code()?, constructor_parameters))
+    }
+}
+
+// A Truffle or Hardhat artifact; Hardhat adds the source name and link references
+#[derive(Deserialize)]
+struct RawArtifact {
+    #[serde(rename = "contractName")]
+    contract_name: String,
+
+    #[serde(rename = "sourceName", default)]
+    source_name: Option<String>,
+
+    abi: Value,
+
+    bytecode: String,
+
+    #[serde(rename = "deployedBytecode")]
+    deployed_bytecode: String,
+
+    #[serde(rename = "linkReferences", default)]
+    link_references: RawLinkReferences,
+
+    #[serde(rename = "deployedLinkReferences", default)]
+    deployed_link_references: RawLinkReferences,
+}
+
+#[derive(Deserialize)]
+struct RawCombinedJson {
+    contracts: BTreeMap<String, RawCombinedContract>,
+}
+
+// Each field is only present if it was asked for
+#[derive(Deserialize)]
+struct RawCombinedContract {
+    #[serde(default)]
+    abi: Value,
+
+    #[serde(default)]
+    bin: String,
+
+    #[serde(rename = "bin-runtime", default)]
+    bin_runtime: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode::{Placeholder, PlaceholderKind};
+
+    // `Token` calls `SafeMath`; the placeholder holds the hash of `lib/SafeMath.sol:SafeMath`
+    const COMBINED_JSON: &str = r#"{
+        "contracts": {
+            "lib/SafeMath.sol:SafeMath": { "abi": [], "bin": "6080", "bin-runtime": "7300" },
+            "token/Token.sol:Token": {
+                "abi": "[{\"type\":\"constructor\",\"inputs\":[{\"type\":\"uint256\"}]}]",
+                "bin": "6080__$e6f95dc827d4d2915bcbe03f1b6d9f5c38$__00",
+                "bin-runtime": "6080"
+            }
+        },
+        "version": "0.5.17"
+    }"#;
+
+    #[test]
+    fn test_combined_json() -> Result<(), AbiError> {
+        let mut token = CompiledContract::from_combined_json(COMBINED_JSON, "Token")?;
+
+        assert_eq!(token.name, "token/Token.sol:Token");
+        assert_eq!(token.bytecode.code.len(), 2 + 20 + 1);
+        assert!(token.abi.constructor.is_some());
+
+        match token.creation_code() {
+            Err(AbiError::UnlinkedLibrary(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let id = ContractId { shard: 0, realm: 0, contract: 3 };
+        token.link("lib/SafeMath.sol:SafeMath", id)?;
+
+        assert_eq!(token.bytecode.code[21], 3);
+        assert_eq!(token.file_contents()?.len(), 2 * 23);
+
+        match token.link("Math", id) {
+            Err(AbiError::UnknownLibrary(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let params = token.constructor_params(&[AbiValue::Uint(1000u32.into())])?;
+        assert_eq!(params.assemble().len(), 32);
+
+        match CompiledContract::from_combined_json(COMBINED_JSON, "Vault") {
+            Err(AbiError::UnknownContract(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hardhat_artifact() -> Result<(), AbiError> {
+        let contract = CompiledContract::from_artifact_json(
+            r#"{
+                "_format": "hh-sol-artifact-1",
+                "contractName": "Token",
+                "sourceName": "contracts/Token.sol",
+                "abi": [],
+                "bytecode": "0x6080__$e6f95dc827d4d2915bcbe03f1b6d9f5c38$__",
+                "deployedBytecode": "0x6080",
+                "linkReferences": {
+                    "lib/SafeMath.sol": { "SafeMath": [{ "start": 2, "length": 20 }] }
+                },
+                "deployedLinkReferences": {}
+            }"#,
+        )?;
+
+        assert_eq!(contract.name, "contracts/Token.sol:Token");
+        assert_eq!(
+            contract.bytecode.placeholders,
+            vec![Placeholder {
+                kind: PlaceholderKind::Library("lib/SafeMath.sol:SafeMath".into()),
+                offset: 2,
+                length: 20,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_truffle_artifact() -> Result<(), AbiError> {
+        let mut contract = CompiledContract::from_artifact_json(&format!(
+            r#"{{
+                "contractName": "Token",
+                "abi": [],
+                "bytecode": "0x6080__{:_<36}__",
+                "deployedBytecode": "0x6080"
+            }}"#,
+            "SafeMath"
+        ))?;
+
+        assert_eq!(contract.name, "Token");
+        assert_eq!(contract.bytecode.unlinked_libraries(), vec!["SafeMath"]);
+
+        let id = ContractId { shard: 0, realm: 0, contract: 3 };
+        contract.link("lib/SafeMath.sol:SafeMath", id)?;
+        assert!(contract.creation_code().is_ok());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,494 @@
+// Compiled contract bytecode, and verification of deployed bytecode against it
+use crate::{
+    abi::keccak256,
+    error::AbiError,
+    query::{Query, QueryContractGetBytecode},
+    SolidityAddress,
+};
+use failure::Error;
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt, ops::Range};
+
+// Library placeholders are written in place of a 20 byte address; 40 hex chars
+const PLACEHOLDER_LEN_HEX: usize = 40;
+const ADDRESS_LEN: usize = 20;
+
+// Placeholders of solc before 0.5 hold the fully qualified name of the library, cut to 36 chars
+const LEGACY_NAME_LEN: usize = 36;
+
+// Placeholders of solc 0.5 and later hold the first 17 bytes of the hash of the name instead
+const NAME_HASH_LEN: usize = 17;
+
+// Libraries start with `PUSH20 <address>`, which is zero in the compiled code
+const PUSH20: u8 = 0x73;
+
+/// A region of compiled bytecode that is only filled in when the contract is deployed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub kind: PlaceholderKind,
+    pub offset: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaceholderKind {
+    /// The address of a library; either the fully qualified name of the library (`file:Name`)
+    /// or the text of the placeholder, such as `$<hash>$`.
+    Library(String),
+
+    /// The value of an `immutable` variable, by the AST ID of its declaration.
+    Immutable(String),
+}
+
+impl Placeholder {
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.length
+    }
+}
+
+/// Bytecode as produced by solc, with the placeholders that are filled in on deployment.
+///
+/// Placeholders are zero in `code`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bytecode {
+    pub code: Vec<u8>,
+    pub placeholders: Vec<Placeholder>,
+}
+
+impl Bytecode {
+    /// Parse hex bytecode, with or without a `0x` prefix, as in the output of `solc --bin` or
+    /// `solc --bin-runtime`.
+    ///
+    /// Library placeholders (`__$<hash>$__` or `__<file>:<Name>__`, padded with `_`) are read
+    /// as zero addresses.
+    pub fn from_hex(hex: &str) -> Result<Self, AbiError> {
+        let hex = hex.trim();
+        let hex = if hex.starts_with("0x") { &hex[2..] } else { hex };
+        let mut rest = hex;
+
+        let mut bytecode = Bytecode::default();
+
+        while let Some(start) = rest.find("__") {
+            bytecode.code.extend(decode_hex(&rest[..start])?);
+
+            let placeholder = rest.get(start..start + PLACEHOLDER_LEN_HEX).unwrap_or("");
+
+            if !placeholder.ends_with("__") {
+                return Err(AbiError::InvalidBytecode(format!(
+                    "unterminated library placeholder at hex char {}",
+                    hex.len() - rest.len() + start
+                )));
+            }
+
+            bytecode.placeholders.push(Placeholder {
+                kind: PlaceholderKind::Library(placeholder.trim_matches('_').to_string()),
+                offset: bytecode.code.len(),
+                length: ADDRESS_LEN,
+            });
+
+            bytecode.code.extend(&[0; ADDRESS_LEN]);
+            rest = &rest[start + PLACEHOLDER_LEN_HEX..];
+        }
+
+        bytecode.code.extend(decode_hex(rest)?);
+
+        Ok(bytecode)
+    }
+
+    /// Parse a solc `evm.bytecode` or `evm.deployedBytecode` JSON object, with its link and
+    /// immutable references.
+    pub fn from_json(json: &str) -> Result<Self, AbiError> {
+        Self::from_raw(serde_json::from_str(json)?)
+    }
+
+    pub(crate) fn from_raw(raw: RawBytecode) -> Result<Self, AbiError> {
+        let mut bytecode = Self::from_hex(&raw.object)?;
+
+        // Replace the placeholders found in the hex with the named references
+        if !raw.link_references.is_empty() {
+            bytecode.placeholders.clear();
+        }
+
+        for (file, libraries) in raw.link_references {
+            for (library, references) in libraries {
+                let name = format!("{}:{}", file, library);
+
+                for reference in references {
+                    bytecode.add_placeholder(PlaceholderKind::Library(name.clone()), reference)?;
+                }
+            }
+        }
+
+        for (id, references) in raw.immutable_references {
+            for reference in references {
+                bytecode.add_placeholder(PlaceholderKind::Immutable(id.clone()), reference)?;
+            }
+        }
+
+        Ok(bytecode)
+    }
+
+    fn add_placeholder(
+        &mut self,
+        kind: PlaceholderKind,
+        reference: RawReference,
+    ) -> Result<(), AbiError> {
+        let placeholder = Placeholder { kind, offset: reference.start, length: reference.length };
+
+        if placeholder.range().end > self.code.len() {
+            return Err(AbiError::InvalidBytecode(format!(
+                "reference to bytes {:?} is out of bounds of {} bytes of code",
+                placeholder.range(),
+                self.code.len()
+            )));
+        }
+
+        self.placeholders.push(placeholder);
+
+        Ok(())
+    }
+
+    /// Write the address of `library` over its placeholders; returns `false` if the bytecode does
+    /// not reference the library.
+    ///
+    /// `library` is the fully qualified name of the library (`file:Name`), or just its name if
+    /// that is all the placeholders hold.
+    pub fn link(&mut self, library: &str, address: SolidityAddress) -> bool {
+        let code = &mut self.code;
+        let mut linked = false;
+
+        self.placeholders.retain(|placeholder| match &placeholder.kind {
+            PlaceholderKind::Library(name)
+                if placeholder.length == ADDRESS_LEN && links(name, library) =>
+            {
+                code[placeholder.range()].copy_from_slice(address.as_bytes());
+                linked = true;
+                false
+            }
+
+            _ => true,
+        });
+
+        linked
+    }
+
+    /// The libraries that have not been linked yet, by their placeholder names.
+    pub fn unlinked_libraries(&self) -> Vec<&str> {
+        let mut libraries: Vec<&str> = self
+            .placeholders
+            .iter()
+            .filter_map(|placeholder| match &placeholder.kind {
+                PlaceholderKind::Library(name) => Some(name.as_str()),
+                PlaceholderKind::Immutable(_) => None,
+            })
+            .collect();
+
+        libraries.sort();
+        libraries.dedup();
+        libraries
+    }
+
+    /// The code without the CBOR-encoded metadata that solc appends to it.
+    pub fn code_without_metadata(&self) -> &[u8] {
+        split_metadata(&self.code).0
+    }
+
+    /// Compare deployed runtime bytecode with this runtime bytecode.
+    ///
+    /// Placeholders and the trailing metadata (which holds the hash of the source, and so
+    /// differs with comments and paths) are ignored.
+    pub fn compare(&self, deployed: &[u8]) -> BytecodeComparison {
+        let (expected, expected_metadata) = split_metadata(&self.code);
+        let (found, found_metadata) = split_metadata(deployed);
+
+        let mut ignored = vec![false; expected.len()];
+
+        for placeholder in &self.placeholders {
+            let range = placeholder.range();
+            let range = range.start.min(expected.len())..range.end.min(expected.len());
+
+            for flag in &mut ignored[range] {
+                *flag = true;
+            }
+        }
+
+        if expected.len() > ADDRESS_LEN
+            && expected[0] == PUSH20
+            && expected[1..=ADDRESS_LEN].iter().all(|b| *b == 0)
+        {
+            for flag in &mut ignored[1..=ADDRESS_LEN] {
+                *flag = true;
+            }
+        }
+
+        let differs = |i: usize| match (expected.get(i), found.get(i)) {
+            (Some(e), Some(f)) => e != f && !ignored[i],
+            _ => true,
+        };
+
+        let mut differences = Vec::new();
+        let mut i = 0;
+        let len = expected.len().max(found.len());
+
+        while i < len {
+            if !differs(i) {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+
+            while i < len && differs(i) {
+                i += 1;
+            }
+
+            differences.push(BytecodeDifference {
+                offset: start,
+                expected: slice(expected, start..i).to_vec(),
+                found: slice(found, start..i).to_vec(),
+            });
+        }
+
+        BytecodeComparison { differences, metadata_matches: expected_metadata == found_metadata }
+    }
+}
+
+/// The result of comparing deployed bytecode with compiled bytecode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BytecodeComparison {
+    /// The runs of bytes that differ, excluding placeholders and metadata.
+    pub differences: Vec<BytecodeDifference>,
+
+    /// If the metadata matches as well; when it does, the contract was compiled from the same
+    /// source with the same settings.
+    pub metadata_matches: bool,
+}
+
+impl BytecodeComparison {
+    /// If the deployed bytecode runs the same code as the compiled bytecode.
+    pub fn is_match(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for BytecodeComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_match() {
+            return f.write_str("bytecode matches");
+        }
+
+        write!(f, "bytecode differs in {} places", self.differences.len())?;
+
+        for difference in &self.differences {
+            write!(f, "\n  {}", difference)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A run of bytes that differ; either side is shorter (or empty) past the end of its code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BytecodeDifference {
+    pub offset: usize,
+    pub expected: Vec<u8>,
+    pub found: Vec<u8>,
+}
+
+impl fmt::Display for BytecodeDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "at byte {}: expected 0x{}, found 0x{}",
+            self.offset,
+            hex::encode(&self.expected),
+            hex::encode(&self.found)
+        )
+    }
+}
+
+/// Compare the runtime bytecode of a contract instance with compiled bytecode; see
+/// `Bytecode::compare`.
+pub struct BytecodeVerifier<'a> {
+    query: Query<QueryContractGetBytecode>,
+    expected: &'a Bytecode,
+}
+
+impl<'a> BytecodeVerifier<'a> {
+    pub(crate) fn new(query: Query<QueryContractGetBytecode>, expected: &'a Bytecode) -> Self {
+        Self { query, expected }
+    }
+
+    pub async fn get_async(&mut self) -> Result<BytecodeComparison, Error> {
+        let deployed = self.query.get_async().await?;
+
+        Ok(self.expected.compare(&deployed))
+    }
+
+    pub fn get(&mut self) -> Result<BytecodeComparison, Error> {
+        crate::RUNTIME
+            .lock()
+            .block_on(self.get_async())
+    }
+}
+
+// If the placeholder `name` is for `library`
+fn links(name: &str, library: &str) -> bool {
+    // Either may leave out the file of the library
+    let bare = |name: &str| name.rsplit(':').next().unwrap_or(name).to_string();
+    let either_bare = !name.contains(':') || !library.contains(':');
+
+    name == library
+        || (either_bare && bare(name) == bare(library))
+        || (name.len() == LEGACY_NAME_LEN && library.starts_with(name))
+        || name == format!("${}$", hex::encode(&keccak256(library.as_bytes())[..NAME_HASH_LEN]))
+}
+
+// Solidity appends CBOR-encoded metadata to the runtime code, followed by its length as 2
+// big-endian bytes
+fn split_metadata(code: &[u8]) -> (&[u8], &[u8]) {
+    if code.len() < 2 {
+        return (code, &[]);
+    }
+
+    let len = usize::from(u16::from_be_bytes([code[code.len() - 2], code[code.len() - 1]]));
+
+    match code.len().checked_sub(len + 2) {
+        // The metadata is a CBOR map; major type 5
+        Some(start) if len > 0 && code[start] >> 5 == 5 => code.split_at(start),
+        _ => (code, &[]),
+    }
+}
+
+fn slice(code: &[u8], range: Range<usize>) -> &[u8] {
+    &code[range.start.min(code.len())..range.end.min(code.len())]
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, AbiError> {
+    hex::decode(hex).map_err(|e| AbiError::InvalidBytecode(format!("invalid hex; {}", e)))
+}
+
+// file -> library -> references
+pub(crate) type RawLinkReferences = BTreeMap<String, BTreeMap<String, Vec<RawReference>>>;
+
+// A bytecode object of the solc standard JSON output
+#[derive(Deserialize)]
+pub(crate) struct RawBytecode {
+    pub(crate) object: String,
+
+    #[serde(rename = "linkReferences", default)]
+    pub(crate) link_references: RawLinkReferences,
+
+    // AST ID -> references
+    #[serde(rename = "immutableReferences", default)]
+    pub(crate) immutable_references: BTreeMap<String, Vec<RawReference>>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RawReference {
+    start: usize,
+    length: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `PUSH1 0x80 PUSH1 0x40 MSTORE`, a library address, and a metadata map of 2 bytes
+    const RUNTIME_HEX: &str =
+        "6080604052 73__$0123456789abcdef0123456789abcdef01$__ 5b a1 00 0002";
+
+    fn runtime() -> Bytecode {
+        Bytecode::from_hex(&RUNTIME_HEX.replace(' ', "")).unwrap()
+    }
+
+    #[test]
+    fn test_from_hex() -> Result<(), AbiError> {
+        let bytecode = runtime();
+
+        assert_eq!(bytecode.code.len(), 5 + 1 + 20 + 1 + 4);
+        assert_eq!(
+            bytecode.placeholders,
+            vec![Placeholder {
+                kind: PlaceholderKind::Library("$0123456789abcdef0123456789abcdef01$".into()),
+                offset: 6,
+                length: 20,
+            }]
+        );
+
+        assert_eq!(bytecode.code_without_metadata().len(), 27);
+        assert_eq!(Bytecode::from_hex("0x6080")?.code, vec![0x60, 0x80]);
+
+        match Bytecode::from_hex("6080__$0123$__") {
+            Err(AbiError::InvalidBytecode(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_json() -> Result<(), AbiError> {
+        let bytecode = Bytecode::from_json(
+            r#"{
+                "object": "608060405260006000",
+                "linkReferences": {},
+                "immutableReferences": { "12": [{ "start": 5, "length": 2 }] }
+            }"#,
+        )?;
+
+        assert_eq!(
+            bytecode.placeholders,
+            vec![Placeholder {
+                kind: PlaceholderKind::Immutable("12".into()),
+                offset: 5,
+                length: 2,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_link() -> Result<(), AbiError> {
+        let mut bytecode = Bytecode::from_hex(&format!(
+            "73__{:_<36}__73__{:_<36}__",
+            "lib/Math.sol:Math", "$a4a2e6e2b3ab2d1d8ca42c0e1e25fa1bc5$"
+        ))?;
+
+        let address = SolidityAddress::from([0x11; 20]);
+
+        assert!(bytecode.link("Math", address));
+        assert!(!bytecode.link("Math", address));
+        assert_eq!(&bytecode.code[1..21], address.as_bytes());
+        assert_eq!(bytecode.unlinked_libraries(), vec!["$a4a2e6e2b3ab2d1d8ca42c0e1e25fa1bc5$"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() {
+        let bytecode = runtime();
+
+        // The library is linked and the metadata differs
+        let mut deployed = bytecode.code.clone();
+        deployed[7..26].copy_from_slice(&[0x11; 19]);
+        deployed[28] = 0x01;
+
+        let comparison = bytecode.compare(&deployed);
+        assert!(comparison.is_match(), "{}", comparison);
+        assert!(!comparison.metadata_matches);
+
+        // The code differs, and is truncated
+        deployed[1] = 0x81;
+        let comparison = bytecode.compare(&deployed[..26]);
+
+        assert_eq!(
+            comparison.differences,
+            vec![
+                BytecodeDifference { offset: 1, expected: vec![0x80], found: vec![0x81] },
+                BytecodeDifference { offset: 26, expected: vec![0x5b], found: vec![] },
+            ]
+        );
+    }
+}
@@ -1,17 +1,49 @@
 // Helper functions for call_params
+use crate::argument::Argument;
+use crate::error::AbiError;
+use num::{bigint::Sign, BigInt, One, Zero};
 
-pub(crate) fn check_fixed_array_len<T>(arr: &[T], fixed_len: usize) {
+pub(crate) fn check_fixed_array_len<T>(arr: &[T], fixed_len: usize) -> Result<(), AbiError> {
     if arr.len() != fixed_len {
-        panic!("ILLEGAL ARGUMENT ERROR: fixedLen ({:#?}) does not match array length \
-        ({:#?})", fixed_len, arr.len());
+        return Err(AbiError::ArrayLength { expected: fixed_len, found: arr.len() });
     }
+    Ok(())
 }
 
-pub(crate) fn check_int_width(width: usize) {
+pub(crate) fn check_int_width(width: usize) -> Result<(), AbiError> {
     if width % 8 != 0 || width < 8 || width > 256 {
-        panic!("ILLEGAL ARGUMENT ERROR: Solidity integer width must be a multiple of 8, in the \
-        closed range [8, 256]");
+        return Err(AbiError::IntWidth(width));
     }
+    Ok(())
+}
+
+pub(crate) fn check_fixed_bytes_len(len: usize) -> Result<(), AbiError> {
+    if len == 0 || len > 32 {
+        return Err(AbiError::InvalidType(format!("bytes{}", len)));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_int_range(val: &BigInt, width: usize, signed: bool) -> Result<(), AbiError> {
+    let (min, max) = if signed {
+        let half = BigInt::one() << (width - 1);
+        (-half.clone(), half - 1)
+    } else {
+        (BigInt::zero(), (BigInt::one() << width) - 1)
+    };
+
+    if *val < min || *val > max {
+        return Err(AbiError::InvalidValue {
+            ty: format!("{}int{}", if signed { "" } else { "u" }, width),
+            reason: format!("{} is out of range", val),
+        });
+    }
+    Ok(())
+}
+
+// The panicking `CallParams` methods predate the fallible ones and keep their message format
+pub(crate) fn illegal_argument<T>(err: AbiError) -> T {
+    panic!("ILLEGAL ARGUMENT ERROR: {}", err)
 }
 
 pub(crate) fn create_padding() -> Vec<u8> {
@@ -49,9 +81,38 @@ pub(crate) fn int256(val: isize) -> Vec<u8> {
     padded_bytes
 }
 
-pub(crate) fn uint256(val: usize) -> Vec<u8> {
-    let padded_bytes = left_pad(val.to_be_bytes().to_vec(), false);
-    padded_bytes
+// Two's complement encoding of a value that has already been range checked
+pub(crate) fn big_int256(val: &BigInt) -> Vec<u8> {
+    match val.sign() {
+        Sign::Minus => left_pad(val.to_signed_bytes_be(), true),
+        _ => left_pad(val.to_bytes_be().1, false),
+    }
+}
+
+// Lay out arguments as their heads followed by their tails, as for function parameters and
+// tuples; dynamic arguments are referenced from their head by an offset relative to the start
+// of the encoding
+pub(crate) fn encode_args(args: &[Argument]) -> Vec<u8> {
+    let mut dynamic_offset: usize = args
+        .iter()
+        .map(|arg| if arg.dynamic { 32 } else { arg.value.len() })
+        .sum();
+
+    let mut head = Vec::<u8>::new();
+    let mut tail = Vec::<u8>::new();
+
+    for arg in args {
+        if arg.dynamic == true {
+            head.extend(int256(dynamic_offset as isize));
+            tail.extend(&arg.value);
+            dynamic_offset += arg.value.len();
+        } else {
+            head.extend(&arg.value);
+        }
+    }
+
+    head.extend(tail);
+    head
 }
 
 pub(crate) fn encode_bytes(b: Vec<u8>) -> Vec<u8> {
@@ -86,38 +147,42 @@ pub(crate) fn encode_byte_array(byte_array: Vec<Vec<u8>>, prepend_len: bool) ->
     bytes
 }
 
-pub(crate) fn encode_int_array(int_array: Vec<isize>, int_width: usize, prepend_len: bool) -> Vec<u8> {
-    check_int_width(int_width);
+pub(crate) fn encode_int_array(int_array: Vec<BigInt>, int_width: usize,
+                               prepend_len: bool) -> Result<Vec<u8>, AbiError> {
+    check_int_width(int_width)?;
 
     let mut bytes = Vec::new();
     for i in int_array.iter() {
-        let enc_i = int256(*i);
+        check_int_range(i, int_width, true)?;
+        let enc_i = big_int256(i);
         bytes.extend(enc_i);
     }
 
     if prepend_len == true {
         let mut enc_bytes = int256(int_array.len() as isize);
         enc_bytes.extend(bytes);
-        return enc_bytes
+        return Ok(enc_bytes)
     }
-    bytes
+    Ok(bytes)
 }
 
-pub(crate) fn encode_uint_array(int_array: Vec<usize>, int_width: usize, prepend_len: bool) -> Vec<u8> {
-    check_int_width(int_width);
+pub(crate) fn encode_uint_array(int_array: Vec<BigInt>, int_width: usize,
+                                prepend_len: bool) -> Result<Vec<u8>, AbiError> {
+    check_int_width(int_width)?;
 
     let mut bytes = Vec::new();
     for i in int_array.iter() {
-        let enc_i = uint256(*i);
+        check_int_range(i, int_width, false)?;
+        let enc_i = big_int256(i);
         bytes.extend(enc_i);
     }
 
     if prepend_len == true {
         let mut enc_bytes = int256(int_array.len() as isize);
         enc_bytes.extend(bytes);
-        return enc_bytes
+        return Ok(enc_bytes)
     }
-    bytes
+    Ok(bytes)
 }
 
 #[cfg(test)]
@@ -163,4 +228,58 @@ mod tests {
             "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffff00000".to_string()
         );
     }
+
+    #[test]
+    fn test_big_int256_encoding() {
+        let max_uint256 = (BigInt::one() << 256) - 1;
+        assert!(check_int_range(&max_uint256, 256, false).is_ok());
+        assert_eq!(
+            hex::encode(big_int256(&max_uint256)),
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff".to_string()
+        );
+
+        let min_int256 = -(BigInt::one() << 255);
+        assert!(check_int_range(&min_int256, 256, true).is_ok());
+        assert_eq!(
+            hex::encode(big_int256(&min_int256)),
+            "8000000000000000000000000000000000000000000000000000000000000000".to_string()
+        );
+
+        // 1,000,000 tokens with 18 decimals
+        let amount = BigInt::from(1_000_000u64) * num::pow(BigInt::from(10u64), 18);
+        assert!(check_int_range(&amount, 256, false).is_ok());
+        assert_eq!(
+            hex::encode(big_int256(&amount)),
+            "00000000000000000000000000000000000000000000d3c21bcecceda1000000".to_string()
+        );
+
+        assert_eq!(hex::encode(big_int256(&BigInt::from(-2))), hex::encode(int256(-2)));
+    }
+
+    #[test]
+    fn test_int_range_errors() {
+        let out_of_range = vec![
+            (BigInt::from(128), 8, true),
+            (BigInt::from(-129), 8, true),
+            (BigInt::from(-1), 256, false),
+            (BigInt::from(256), 8, false),
+        ];
+
+        for (val, width, signed) in out_of_range {
+            match check_int_range(&val, width, signed) {
+                Err(AbiError::InvalidValue { .. }) => {}
+                result => panic!("expected {} to be out of range; got {:?}", val, result),
+            }
+        }
+
+        match check_int_width(12) {
+            Err(AbiError::IntWidth(12)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match check_fixed_array_len(&[1, 2, 3], 2) {
+            Err(AbiError::ArrayLength { expected: 2, found: 3 }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
 }
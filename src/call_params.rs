@@ -1,13 +1,23 @@
+use crate::abi::{self, AbiValue, ParamType};
 use crate::argument::Argument;
 use crate::function_selector::{FunctionSelector, SELECTOR_LEN, SELECTOR_LEN_HEX};
 use crate::call_param_utils::*;
-use crate::solidity_util::{check_address_len, decode_address};
+use crate::error::AbiError;
+use crate::solidity_util::{check_address_len, decode_address, SolidityAddress};
 use std::string::ToString;
 use hex;
-
+use num::BigInt;
+
+/// Parameters of a contract function call or constructor, encoded as they are added.
+///
+/// Every `add_*` method panics on an invalid argument, such as a malformed address or an
+/// integer that is out of range for its width. Each has a `try_add_*` counterpart that returns
+/// an `AbiError` instead and leaves the parameters unchanged; use those for values that come
+/// from user input.
 #[derive(Clone)]
 pub struct CallParams {
     pub(crate) func_selector: Option<FunctionSelector>,
+    pub(crate) param_types: Vec<String>,
     pub(crate) args: Vec<Argument>
 }
 
@@ -20,11 +30,14 @@ impl CallParams {
         let a = Vec::new();
         Self {
             func_selector: fs,
+            param_types: Vec::new(),
             args: a
         }
     }
 
     fn add_param_type(&mut self, param_type: String) {
+        self.param_types.push(param_type.clone());
+
         match self.func_selector.clone() {
             Some(mut fs) => {
                 fs.add_param_type(param_type);
@@ -35,14 +48,27 @@ impl CallParams {
         };
     }
 
+    fn push_arg(&mut self, param_type: String, arg: Argument) {
+        self.add_param_type(param_type);
+        self.args.push(arg);
+    }
+
     pub fn add_string(&mut self, param: String) {
+        self.try_add_string(param).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_string(&mut self, param: String) -> Result<(), AbiError> {
         let enc_string = encode_string(param);
-        let arg = Argument::new(enc_string, true);
-        self.add_param_type("string".to_string());
-        self.args.push(arg);
+        let arg = Argument::new(enc_string, true)?;
+        self.push_arg("string".to_string(), arg);
+        Ok(())
     }
 
     pub fn add_string_array(&mut self, param: Vec<String>) {
+        self.try_add_string_array(param).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_string_array(&mut self, param: Vec<String>) -> Result<(), AbiError> {
         let mut bytes = Vec::new();
         for s in param {
             let es = encode_string(s);
@@ -50,14 +76,18 @@ impl CallParams {
         }
 
         let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-
-        self.add_param_type("string[]".to_string());
-        self.args.push(arg);
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg("string[]".to_string(), arg);
+        Ok(())
     }
 
     pub fn add_fixed_string_array(&mut self, param: Vec<String>, fixed_len: usize) {
-        check_fixed_array_len(&param[..], fixed_len);
+        self.try_add_fixed_string_array(param, fixed_len).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_fixed_string_array(&mut self, param: Vec<String>, fixed_len: usize)
+                                      -> Result<(), AbiError> {
+        check_fixed_array_len(&param[..], fixed_len)?;
 
         let mut bytes = Vec::new();
         for s in param {
@@ -66,36 +96,42 @@ impl CallParams {
         }
 
         let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-
-        let param_type = format!("string[{:#?}]", fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("string[{:#?}]", fixed_len), arg);
+        Ok(())
     }
 
     pub fn add_bytes(&mut self, param: Vec<u8>) {
+        self.try_add_bytes(param).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_bytes(&mut self, param: Vec<u8>) -> Result<(), AbiError> {
         let enc_bytes = encode_bytes(param);
-        let arg = Argument::new(enc_bytes, true);
-        self.add_param_type("bytes".to_string());
-        self.args.push(arg);
+        let arg = Argument::new(enc_bytes, true)?;
+        self.push_arg("bytes".to_string(), arg);
+        Ok(())
     }
 
     pub fn add_fixed_bytes(&mut self, param: Vec<u8>, fixed_len: usize) {
-        check_fixed_array_len(&param[..], fixed_len);
+        self.try_add_fixed_bytes(param, fixed_len).unwrap_or_else(illegal_argument)
+    }
 
-        if fixed_len > 32 {
-            panic!("ILLEGAL ARGUMENT ERROR: bytesN cannot have a length greater than 32; \
-            given length: {:#?}", fixed_len);
-        }
+    pub fn try_add_fixed_bytes(&mut self, param: Vec<u8>, fixed_len: usize)
+                               -> Result<(), AbiError> {
+        check_fixed_array_len(&param[..], fixed_len)?;
+        check_fixed_bytes_len(fixed_len)?;
 
         let enc_bytes = encode_fixed_bytes(param);
-        let arg = Argument::new(enc_bytes, false);
-        let param_type = format!("bytes{:#?}", fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        let arg = Argument::new(enc_bytes, false)?;
+        self.push_arg(format!("bytes{:#?}", fixed_len), arg);
+        Ok(())
     }
 
     pub fn add_byte_array(&mut self, param: Vec<Vec<u8>>) {
+        self.try_add_byte_array(param).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_byte_array(&mut self, param: Vec<Vec<u8>>) -> Result<(), AbiError> {
         let mut bytes = Vec::new();
         for b in param {
             let be = encode_bytes(b);
@@ -103,20 +139,21 @@ impl CallParams {
         }
 
         let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-        self.add_param_type("bytes[]".to_string());
-        self.args.push(arg);
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg("bytes[]".to_string(), arg);
+        Ok(())
     }
 
     pub fn add_fixed_byte_array(&mut self, param: Vec<Vec<u8>>, byte_len: usize) {
-        for b in param.clone() {
-            check_fixed_array_len(&b[..], byte_len);
-        }
+        self.try_add_fixed_byte_array(param, byte_len).unwrap_or_else(illegal_argument)
+    }
 
-        if byte_len > 32 {
-            panic!("ILLEGAL ARGUMENT ERROR: bytesN cannot have a length greater than 32; \
-            given length: {:#?}", byte_len);
+    pub fn try_add_fixed_byte_array(&mut self, param: Vec<Vec<u8>>, byte_len: usize)
+                                    -> Result<(), AbiError> {
+        for b in &param {
+            check_fixed_array_len(&b[..], byte_len)?;
         }
+        check_fixed_bytes_len(byte_len)?;
 
         let mut bytes = Vec::new();
         for b in param {
@@ -125,14 +162,18 @@ impl CallParams {
         }
 
         let enc_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(enc_bytes, true);
-        let param_type = format!("bytes{:#?}[]", byte_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        let arg = Argument::new(enc_bytes, true)?;
+        self.push_arg(format!("bytes{:#?}[]", byte_len), arg);
+        Ok(())
     }
 
     pub fn add_byte_fixed_array(&mut self, param: Vec<Vec<u8>>, fixed_len: usize) {
-        check_fixed_array_len(&param[..], fixed_len);
+        self.try_add_byte_fixed_array(param, fixed_len).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_byte_fixed_array(&mut self, param: Vec<Vec<u8>>, fixed_len: usize)
+                                    -> Result<(), AbiError> {
+        check_fixed_array_len(&param[..], fixed_len)?;
 
         let mut bytes = Vec::new();
         for b in param {
@@ -141,179 +182,240 @@ impl CallParams {
         }
 
         let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("bytes[{:#?}]", fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("bytes[{:#?}]", fixed_len), arg);
+        Ok(())
     }
 
     pub fn add_fixed_byte_fixed_array(&mut self, param: Vec<Vec<u8>>, fixed_byte_len: usize,
                                       fixed_len: usize) {
-        check_fixed_array_len(&param[..], fixed_len);
-        for b in param.clone() {
-            check_fixed_array_len(&b[..], fixed_byte_len);
+        self.try_add_fixed_byte_fixed_array(param, fixed_byte_len, fixed_len)
+            .unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_fixed_byte_fixed_array(&mut self, param: Vec<Vec<u8>>, fixed_byte_len: usize,
+                                          fixed_len: usize) -> Result<(), AbiError> {
+        check_fixed_array_len(&param[..], fixed_len)?;
+        for b in &param {
+            check_fixed_array_len(&b[..], fixed_byte_len)?;
         }
+        check_fixed_bytes_len(fixed_byte_len)?;
 
         let mut bytes = Vec::new();
-        for b in param.clone() {
+        for b in param {
             let be = encode_fixed_bytes(b);
             bytes.push(be);
         }
 
         let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("bytes{:#?}[{:#?}]", fixed_byte_len, fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("bytes{:#?}[{:#?}]", fixed_byte_len, fixed_len), arg);
+        Ok(())
     }
 
     pub fn add_bool(&mut self, param: bool) {
+        self.try_add_bool(param).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_bool(&mut self, param: bool) -> Result<(), AbiError> {
         let mut val = 0isize;
         if param == true { val = 1isize; }
 
         let enc_bool = int256(val);
-        let arg = Argument::new(enc_bool, false);
-        self.add_param_type("bool".to_string());
-        self.args.push(arg);
+        let arg = Argument::new(enc_bool, false)?;
+        self.push_arg("bool".to_string(), arg);
+        Ok(())
     }
 
-    pub fn add_int(&mut self, param: isize, width: usize) {
-        check_int_width(width);
+    /// Add a signed integer of the given width in bits; `width` must be a multiple of 8 in the
+    /// closed range [8, 256] and `param` must fit within it.
+    pub fn add_int(&mut self, param: impl Into<BigInt>, width: usize) {
+        self.try_add_int(param, width).unwrap_or_else(illegal_argument)
+    }
 
-        let enc_int = int256(param);
-        let arg = Argument::new(enc_int, false);
-        let param_type = format!("int{:#?}", width);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+    pub fn try_add_int(&mut self, param: impl Into<BigInt>, width: usize)
+                       -> Result<(), AbiError> {
+        check_int_width(width)?;
+
+        let param = param.into();
+        check_int_range(&param, width, true)?;
+
+        let enc_int = big_int256(&param);
+        let arg = Argument::new(enc_int, false)?;
+        self.push_arg(format!("int{:#?}", width), arg);
+        Ok(())
     }
 
-    pub fn add_int_array(&mut self, param: Vec<isize>, width: usize) {
-        let arg_bytes = encode_int_array(param, width, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("int{:#?}[]", width);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+    pub fn add_int_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize) {
+        self.try_add_int_array(param, width).unwrap_or_else(illegal_argument)
     }
 
-    pub fn add_fixed_int_array(&mut self, param: Vec<isize>, width: usize, fixed_len: usize) {
-        check_fixed_array_len(&param[..], fixed_len);
+    pub fn try_add_int_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize)
+                                              -> Result<(), AbiError> {
+        let param = param.into_iter().map(Into::into).collect();
+        let arg_bytes = encode_int_array(param, width, true)?;
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("int{:#?}[]", width), arg);
+        Ok(())
+    }
 
-        let arg_bytes = encode_int_array(param, width, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("int{:#?}[{:#?}]", width, fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+    pub fn add_fixed_int_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize,
+                                                fixed_len: usize) {
+        self.try_add_fixed_int_array(param, width, fixed_len).unwrap_or_else(illegal_argument)
     }
 
-    pub fn add_uint(&mut self, param: usize, width: usize) {
-        check_int_width(width);
+    pub fn try_add_fixed_int_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize,
+                                                    fixed_len: usize) -> Result<(), AbiError> {
+        check_fixed_array_len(&param[..], fixed_len)?;
 
-        let enc_uint = uint256(param);
-        let arg = Argument::new(enc_uint, false);
-        let param_type = format!("uint{:#?}", width);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        let param = param.into_iter().map(Into::into).collect();
+        let arg_bytes = encode_int_array(param, width, true)?;
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("int{:#?}[{:#?}]", width, fixed_len), arg);
+        Ok(())
     }
 
-    pub fn add_uint_array(&mut self, param: Vec<usize>, width: usize) {
-        let arg_bytes = encode_uint_array(param, width, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("uint{:#?}[]", width);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+    /// Add an unsigned integer of the given width in bits; `width` must be a multiple of 8 in
+    /// the closed range [8, 256] and `param` must be non-negative and fit within it.
+    ///
+    /// Accepts any primitive integer as well as `BigUint` and `BigInt`.
+    pub fn add_uint(&mut self, param: impl Into<BigInt>, width: usize) {
+        self.try_add_uint(param, width).unwrap_or_else(illegal_argument)
     }
 
-    pub fn add_fixed_uint_array(&mut self, param: Vec<usize>, width: usize, fixed_len: usize) {
-        check_fixed_array_len(&param[..], fixed_len);
+    pub fn try_add_uint(&mut self, param: impl Into<BigInt>, width: usize)
+                        -> Result<(), AbiError> {
+        check_int_width(width)?;
 
-        let arg_bytes = encode_uint_array(param, width, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("uint{:#?}[{:#?}]", width, fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        let param = param.into();
+        check_int_range(&param, width, false)?;
+
+        let enc_uint = big_int256(&param);
+        let arg = Argument::new(enc_uint, false)?;
+        self.push_arg(format!("uint{:#?}", width), arg);
+        Ok(())
     }
 
-    pub fn add_address(&mut self, addr: Vec<u8>) {
-        check_address_len(addr.clone());
+    pub fn add_uint_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize) {
+        self.try_add_uint_array(param, width).unwrap_or_else(illegal_argument)
+    }
 
-        let enc_addr = left_pad(addr, false);
-        let arg = Argument::new(enc_addr, false);
-        self.add_param_type("address".to_string());
-        self.args.push(arg);
+    pub fn try_add_uint_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize)
+                                               -> Result<(), AbiError> {
+        let param = param.into_iter().map(Into::into).collect();
+        let arg_bytes = encode_uint_array(param, width, true)?;
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("uint{:#?}[]", width), arg);
+        Ok(())
+    }
+
+    pub fn add_fixed_uint_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize,
+                                                 fixed_len: usize) {
+        self.try_add_fixed_uint_array(param, width, fixed_len).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_fixed_uint_array<T: Into<BigInt>>(&mut self, param: Vec<T>, width: usize,
+                                                     fixed_len: usize) -> Result<(), AbiError> {
+        check_fixed_array_len(&param[..], fixed_len)?;
+
+        let param = param.into_iter().map(Into::into).collect();
+        let arg_bytes = encode_uint_array(param, width, true)?;
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("uint{:#?}[{:#?}]", width, fixed_len), arg);
+        Ok(())
+    }
+
+    pub fn add_address(&mut self, addr: SolidityAddress) {
+        self.try_add_address(addr).unwrap_or_else(illegal_argument)
     }
 
+    pub fn try_add_address(&mut self, addr: SolidityAddress) -> Result<(), AbiError> {
+        let enc_addr = left_pad(addr.to_vec(), false);
+        let arg = Argument::new(enc_addr, false)?;
+        self.push_arg("address".to_string(), arg);
+        Ok(())
+    }
+
+    /// Add an address given as its 20 bytes.
+    pub fn add_address_bytes(&mut self, addr: Vec<u8>) {
+        self.try_add_address_bytes(addr).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_address_bytes(&mut self, addr: Vec<u8>) -> Result<(), AbiError> {
+        self.try_add_address(SolidityAddress::from_slice(&addr)?)
+    }
+
+    /// Add an address given as 40 hex chars, with or without a `0x` prefix.
     pub fn add_address_string(&mut self, addr: String) {
-        let a = decode_address(addr);
-        self.add_address(a);
+        self.try_add_address_string(addr).unwrap_or_else(illegal_argument)
     }
 
-    pub fn add_address_array(&mut self, addrs: Vec<Vec<u8>>) {
-        let mut bytes = Vec::new();
-        for a in addrs.clone() {
-            check_address_len(a.clone());
-            let ea = left_pad(a, false);
-            bytes.push(ea);
-        }
+    pub fn try_add_address_string(&mut self, addr: String) -> Result<(), AbiError> {
+        self.try_add_address(addr.parse()?)
+    }
 
-        let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-        self.add_param_type("address[]".to_string());
-        self.args.push(arg);
+    pub fn add_address_array(&mut self, addrs: Vec<SolidityAddress>) {
+        self.try_add_address_array(addrs).unwrap_or_else(illegal_argument)
     }
 
-    pub fn add_fixed_address_array(&mut self, addrs: Vec<Vec<u8>>, fixed_len: usize) {
-        check_fixed_array_len(&addrs[..], fixed_len);
+    pub fn try_add_address_array(&mut self, addrs: Vec<SolidityAddress>)
+                                 -> Result<(), AbiError> {
+        let arg = Self::address_array_argument(&addrs)?;
+        self.push_arg("address[]".to_string(), arg);
+        Ok(())
+    }
 
-        let mut bytes = Vec::new();
-        for a in addrs.clone() {
-            check_address_len(a.clone());
-            let ea = left_pad(a, false);
-            bytes.push(ea);
-        }
+    pub fn add_fixed_address_array(&mut self, addrs: Vec<SolidityAddress>, fixed_len: usize) {
+        self.try_add_fixed_address_array(addrs, fixed_len).unwrap_or_else(illegal_argument)
+    }
 
-        let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("address[{:#?}]", fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+    pub fn try_add_fixed_address_array(&mut self, addrs: Vec<SolidityAddress>, fixed_len: usize)
+                                       -> Result<(), AbiError> {
+        check_fixed_array_len(&addrs[..], fixed_len)?;
+
+        let arg = Self::address_array_argument(&addrs)?;
+        self.push_arg(format!("address[{:#?}]", fixed_len), arg);
+        Ok(())
     }
 
     pub fn add_address_string_array(&mut self, addrs: Vec<String>) {
-        let mut bytes = Vec::new();
-        for a in addrs.clone() {
-            let da = decode_address(a);
-            let ea = left_pad(da, false);
-            bytes.push(ea);
-        }
+        self.try_add_address_string_array(addrs).unwrap_or_else(illegal_argument)
+    }
 
-        let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-        self.add_param_type("address[]".to_string());
-        self.args.push(arg);
+    pub fn try_add_address_string_array(&mut self, addrs: Vec<String>) -> Result<(), AbiError> {
+        let addrs = addrs.iter().map(|a| a.parse()).collect::<Result<_, _>>()?;
+        self.try_add_address_array(addrs)
     }
 
     pub fn add_fixed_address_string_array(&mut self, addrs: Vec<String>, fixed_len: usize) {
-        check_fixed_array_len(&addrs[..], fixed_len);
+        self.try_add_fixed_address_string_array(addrs, fixed_len)
+            .unwrap_or_else(illegal_argument)
+    }
 
-        let mut bytes = Vec::new();
-        for a in addrs.clone() {
-            let da = decode_address(a);
-            let ea = left_pad(da, false);
-            bytes.push(ea);
-        }
+    pub fn try_add_fixed_address_string_array(&mut self, addrs: Vec<String>, fixed_len: usize)
+                                              -> Result<(), AbiError> {
+        let addrs = addrs.iter().map(|a| a.parse()).collect::<Result<_, _>>()?;
+        self.try_add_fixed_address_array(addrs, fixed_len)
+    }
 
+    fn address_array_argument(addrs: &[SolidityAddress]) -> Result<Argument, AbiError> {
+        let bytes = addrs.iter().map(|a| left_pad(a.to_vec(), false)).collect();
         let arg_bytes = encode_byte_array(bytes, true);
-        let arg = Argument::new(arg_bytes, true);
-        let param_type = format!("address[{:#?}]", fixed_len);
-        self.add_param_type(param_type);
-        self.args.push(arg);
+        Argument::new(arg_bytes, true)
     }
 
     pub fn add_function(&mut self, addr: Vec<u8>, selector: Vec<u8>) {
-        check_address_len(addr.clone());
+        self.try_add_function(addr, selector).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_function(&mut self, addr: Vec<u8>, selector: Vec<u8>)
+                            -> Result<(), AbiError> {
+        check_address_len(&addr)?;
 
-        if selector.len() != SELECTOR_LEN as usize{
-            panic!("ILLEGAL ARGUMENT ERROR: function selectors must be 4 bytes or 8 hex chars")
+        if selector.len() != SELECTOR_LEN as usize {
+            return Err(AbiError::InvalidSelector(format!(
+                "function selectors must be 4 bytes or 8 hex chars; found {} bytes",
+                selector.len())));
         }
 
         let mut bytes = Vec::new();
@@ -321,70 +423,172 @@ impl CallParams {
         bytes.extend(selector);
 
         let arg_bytes = right_pad(bytes);
-        let arg = Argument::new(arg_bytes, false);
-        self.add_param_type("function".to_string());
-        self.args.push(arg);
+        let arg = Argument::new(arg_bytes, false)?;
+        self.push_arg("function".to_string(), arg);
+        Ok(())
     }
 
     pub fn add_function_string(&mut self, addr: String, selector: String) {
+        self.try_add_function_string(addr, selector).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_function_string(&mut self, addr: String, selector: String)
+                                   -> Result<(), AbiError> {
         if selector.chars().count() != SELECTOR_LEN_HEX as usize {
-            panic!("ILLEGAL ARGUMENT ERROR: function selectors must be 4 bytes or 8 hex chars")
+            return Err(AbiError::InvalidSelector(format!(
+                "function selectors must be 4 bytes or 8 hex chars; found {:?}", selector)));
         }
 
-        let s_bytes = match hex::decode(selector) {
-            Ok(s) => s.to_vec(),
-            Err(e) => panic!("failed to decode Solidity function selector as hex; {:#?}", e),
-        };
+        let s_bytes = hex::decode(&selector).map_err(|e| AbiError::InvalidSelector(format!(
+            "failed to decode {:?} as hex; {}", selector, e)))?;
 
-        let a_bytes = match hex::decode(addr) {
-            Ok(a) => a.to_vec(),
-            Err(e) => panic!("failed to decode Solidity function selector as hex; {:#?}", e),
-        };
-        self.add_function(a_bytes, s_bytes);
+        let a_bytes = decode_address(&addr)?;
+        self.try_add_function(a_bytes, s_bytes)
     }
 
     pub fn add_function_fs(&mut self, addr: String, selector: FunctionSelector) {
-        let a_bytes = match hex::decode(addr) {
-            Ok(a) => a.to_vec(),
-            Err(e) => panic!("failed to decode Solidity function selector as hex; {:#?}", e),
-        };
+        self.try_add_function_fs(addr, selector).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_function_fs(&mut self, addr: String, selector: FunctionSelector)
+                               -> Result<(), AbiError> {
+        let a_bytes = decode_address(&addr)?;
 
         let fs = selector.finish_intermediate();
-        self.add_function(a_bytes, fs[..4].to_vec())
+        self.try_add_function(a_bytes, fs[..4].to_vec())
+    }
+
+    // The canonical type of a tuple with the parameters of `params` as its members
+    fn tuple_type(params: &CallParams) -> String {
+        format!("({})", params.param_types.join(","))
+    }
+
+    // Check that all tuples of an array have the same member types and return that type
+    fn tuple_array_type(tuples: &[CallParams]) -> Result<String, AbiError> {
+        let tuple_type = match tuples.first() {
+            Some(tuple) => Self::tuple_type(tuple),
+            None => return Err(AbiError::InvalidValue {
+                ty: "tuple[]".to_string(),
+                reason: "cannot determine the type of an empty tuple array; \
+                use `add_typed_tuple_array`".to_string(),
+            }),
+        };
+
+        Self::check_tuple_array_type(tuple_type, tuples)
+    }
+
+    // Check that all tuples of an array are of `tuple_type`
+    fn check_tuple_array_type(tuple_type: String, tuples: &[CallParams])
+                              -> Result<String, AbiError> {
+        for t in tuples {
+            if Self::tuple_type(t) != tuple_type {
+                return Err(AbiError::InvalidValue {
+                    ty: format!("{}[]", tuple_type),
+                    reason: format!("tuple array elements must all have the same type; \
+                    found {}", Self::tuple_type(t)),
+                });
+            }
+        }
+
+        Ok(tuple_type)
+    }
+
+    fn tuple_argument(tuple: &CallParams) -> Result<Argument, AbiError> {
+        let dynamic = tuple.args.iter().any(|arg| arg.dynamic);
+        Argument::new(encode_args(&tuple.args), dynamic)
+    }
+
+    /// Add a tuple (a Solidity `struct`) whose members are the parameters added to `tuple`.
+    ///
+    /// The function name of `tuple`, if any, is ignored; create it with `CallParams::new(None)`.
+    pub fn add_tuple(&mut self, tuple: CallParams) {
+        self.try_add_tuple(tuple).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_tuple(&mut self, tuple: CallParams) -> Result<(), AbiError> {
+        let arg = Self::tuple_argument(&tuple)?;
+        self.push_arg(Self::tuple_type(&tuple), arg);
+        Ok(())
+    }
+
+    /// Add a dynamic array of tuples. Every tuple must have the same member types.
+    pub fn add_tuple_array(&mut self, tuples: Vec<CallParams>) {
+        self.try_add_tuple_array(tuples).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_tuple_array(&mut self, tuples: Vec<CallParams>) -> Result<(), AbiError> {
+        let tuple_type = Self::tuple_array_type(&tuples)?;
+        self.push_tuple_array(tuple_type, &tuples)
+    }
+
+    /// Add a dynamic array of tuples of `tuple_type`, which must be a `ParamType::Tuple`.
+    ///
+    /// Unlike `add_tuple_array`, the array may be empty.
+    pub fn add_typed_tuple_array(&mut self, tuple_type: &ParamType, tuples: Vec<CallParams>) {
+        self.try_add_typed_tuple_array(tuple_type, tuples).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_typed_tuple_array(&mut self, tuple_type: &ParamType, tuples: Vec<CallParams>)
+                                     -> Result<(), AbiError> {
+        match tuple_type {
+            ParamType::Tuple(_) => {}
+            _ => return Err(AbiError::InvalidType(format!("{}[]", tuple_type))),
+        }
+
+        let tuple_type = Self::check_tuple_array_type(tuple_type.to_string(), &tuples)?;
+        self.push_tuple_array(tuple_type, &tuples)
+    }
+
+    fn push_tuple_array(&mut self, tuple_type: String, tuples: &[CallParams])
+                        -> Result<(), AbiError> {
+        let elements = tuples.iter().map(Self::tuple_argument).collect::<Result<Vec<_>, _>>()?;
+        let mut arg_bytes = int256(elements.len() as isize);
+        arg_bytes.extend(encode_args(&elements));
+
+        let arg = Argument::new(arg_bytes, true)?;
+        self.push_arg(format!("{}[]", tuple_type), arg);
+        Ok(())
+    }
+
+    /// Add a fixed size array of tuples. Every tuple must have the same member types.
+    pub fn add_fixed_tuple_array(&mut self, tuples: Vec<CallParams>, fixed_len: usize) {
+        self.try_add_fixed_tuple_array(tuples, fixed_len).unwrap_or_else(illegal_argument)
+    }
+
+    pub fn try_add_fixed_tuple_array(&mut self, tuples: Vec<CallParams>, fixed_len: usize)
+                                     -> Result<(), AbiError> {
+        check_fixed_array_len(&tuples[..], fixed_len)?;
+        let tuple_type = Self::tuple_array_type(&tuples)?;
+
+        let elements = tuples.iter().map(Self::tuple_argument).collect::<Result<Vec<_>, _>>()?;
+        let dynamic = elements.iter().any(|arg| arg.dynamic);
+
+        let arg = Argument::new(encode_args(&elements), dynamic)?;
+        self.push_arg(format!("{}[{:#?}]", tuple_type, fixed_len), arg);
+        Ok(())
+    }
+
+    /// Add a dynamic ABI value of any type; the value is checked against `ty` rather than
+    /// causing a panic.
+    pub fn add_value(&mut self, ty: &ParamType, value: &AbiValue) -> Result<(), AbiError> {
+        let arg = Argument::new(abi::encode_value(ty, value)?, ty.is_dynamic())?;
+        self.push_arg(ty.to_string(), arg);
+        Ok(())
     }
 
     pub fn assemble(&self) -> Vec<u8> {
-        let mut dynamic_offset = self.args.len() * 32;
-        let mut param_bytes = Vec::new();
+        let mut out = Vec::new();
 
         match self.func_selector.clone() {
             Some(fs) => {
                 let f = fs.finish_intermediate();
-                param_bytes.push(f[..4].to_vec());
+                out.extend(f[..4].to_vec());
                 ()
             },
             None => (),
         };
 
-        let mut dynamic_bytes = Vec::new();
-
-        for arg in self.args.clone() {
-            if arg.dynamic == true {
-                let offset = int256(dynamic_offset as isize);
-                param_bytes.push(offset);
-                dynamic_bytes.push(arg.clone().value);
-                dynamic_offset += arg.value.len();
-            } else {
-                param_bytes.push(arg.value);
-            }
-        }
-
-        param_bytes.extend(dynamic_bytes);
-
-        let mut out = Vec::new();
-        for b in param_bytes {
-            out.extend(b);
-        }
+        out.extend(encode_args(&self.args));
         out
     }
 }
@@ -435,6 +639,184 @@ mod tests {
         assert_eq!(params, correct);
     }
 
+    #[test]
+    fn test_big_int_params_encoding() {
+        let correct = "a9059cbb000000000000000000000000001122334455667788990011223344556677\
+        889900000000000000000000000000000000000000000000d3c21bcecceda1000000".to_string();
+
+        // 1,000,000 tokens with 18 decimals
+        let amount = num::pow(num::BigUint::from(10u64), 24);
+
+        let mut cp = CallParams::new(Some("transfer".to_string()));
+        cp.add_address_string("0011223344556677889900112233445566778899".to_string());
+        cp.add_uint(amount, 256);
+        let params = hex::encode(cp.assemble());
+
+        assert_eq!(params, correct);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_int_out_of_range() {
+        let mut cp = CallParams::new(None);
+        cp.add_int(-129, 8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_uint_negative() {
+        let mut cp = CallParams::new(None);
+        cp.add_uint(-1, 256);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut cp = CallParams::new(Some("transfer".to_string()));
+
+        match cp.try_add_address_string("0x11223344556677889900112233445566778899".to_string()) {
+            Err(AbiError::InvalidAddress(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match cp.try_add_uint(1, 7) {
+            Err(AbiError::IntWidth(7)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match cp.try_add_fixed_int_array(vec![1, 2, 3], 8, 2) {
+            Err(AbiError::ArrayLength { expected: 2, found: 3 }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match cp.try_add_fixed_bytes(vec![0; 33], 33) {
+            Err(AbiError::InvalidType(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match cp.try_add_tuple_array(Vec::new()) {
+            Err(AbiError::InvalidValue { .. }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // Rejected arguments leave the parameters unchanged
+        assert!(cp.args.is_empty());
+        assert_eq!(hex::encode(cp.assemble()), "8a4068dd");
+    }
+
+    #[test]
+    fn test_tuple_params_encoding() {
+        let correct = "0000000000000000000000000000000000000000000000000000000000000005\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        0000000000000000000000000000000000000000000000000000000000000060\
+        0000000000000000000000000000000000000000000000000000000000000002\
+        0000000000000000000000000000000000000000000000000000000000000040\
+        00000000000000000000000000000000000000000000000000000000000000c0\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        0000000000000000000000000000000000000000000000000000000000000040\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        6100000000000000000000000000000000000000000000000000000000000000\
+        0000000000000000000000000000000000000000000000000000000000000002\
+        0000000000000000000000000000000000000000000000000000000000000040\
+        0000000000000000000000000000000000000000000000000000000000000001\
+        6200000000000000000000000000000000000000000000000000000000000000".to_string();
+
+        let mut static_tuple = CallParams::new(None);
+        static_tuple.add_uint(5, 256);
+        static_tuple.add_bool(true);
+
+        let mut first = CallParams::new(None);
+        first.add_uint(1, 256);
+        first.add_string("a".to_string());
+
+        let mut second = CallParams::new(None);
+        second.add_uint(2, 256);
+        second.add_string("b".to_string());
+
+        let mut cp = CallParams::new(None);
+        cp.add_tuple(static_tuple);
+        cp.add_tuple_array(vec![first, second]);
+        let params = hex::encode(cp.assemble());
+
+        assert_eq!(params, correct);
+    }
+
+    #[test]
+    fn test_empty_tuple_array_encoding() -> Result<(), AbiError> {
+        let correct = "0000000000000000000000000000000000000000000000000000000000000020\
+        0000000000000000000000000000000000000000000000000000000000000000".to_string();
+
+        let tuple_type: ParamType = "(uint256,string)".parse()?;
+
+        let mut cp = CallParams::new(None);
+        cp.try_add_typed_tuple_array(&tuple_type, Vec::new())?;
+
+        assert_eq!(hex::encode(cp.assemble()), correct);
+        assert_eq!(cp.param_types, vec!["(uint256,string)[]".to_string()]);
+
+        let mut other = CallParams::new(None);
+        other.add_bool(true);
+
+        match cp.try_add_typed_tuple_array(&tuple_type, vec![other]) {
+            Err(AbiError::InvalidValue { .. }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match cp.try_add_typed_tuple_array(&ParamType::Bool, Vec::new()) {
+            Err(AbiError::InvalidType(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tuple_function_selector() {
+        // f((uint256,uint256[],(uint256,uint256)[]),(uint256,uint256),uint256)
+        let correct = "6f2be728".to_string();
+
+        let mut t = CallParams::new(None);
+        t.add_uint(1, 256);
+        t.add_uint(2, 256);
+
+        let mut s = CallParams::new(None);
+        s.add_uint(1, 256);
+        s.add_uint_array(vec![1, 2], 256);
+        s.add_tuple_array(vec![t.clone(), t.clone()]);
+
+        let mut cp = CallParams::new(Some("f".to_string()));
+        cp.add_tuple(s);
+        cp.add_tuple(t);
+        cp.add_uint(3, 256);
+        let params = hex::encode(cp.assemble());
+
+        assert_eq!(params[0..8].to_string(), correct);
+    }
+
+    #[test]
+    fn test_fixed_tuple_array_encoding() {
+        // A fixed array of static tuples is encoded in place
+        let correct = "0000000000000000000000000000000000000000000000000000000000000001\
+        0000000000000000000000000000000000000000000000000000000000000002\
+        0000000000000000000000000000000000000000000000000000000000000003\
+        0000000000000000000000000000000000000000000000000000000000000004\
+        0000000000000000000000000000000000000000000000000000000000000005".to_string();
+
+        let mut first = CallParams::new(None);
+        first.add_uint(1, 8);
+        first.add_uint(2, 8);
+
+        let mut second = CallParams::new(None);
+        second.add_uint(3, 8);
+        second.add_uint(4, 8);
+
+        let mut cp = CallParams::new(None);
+        cp.add_fixed_tuple_array(vec![first, second], 2);
+        cp.add_uint(5, 8);
+        let params = hex::encode(cp.assemble());
+
+        assert_eq!(params, correct);
+    }
+
     // TODO: Figure out how to create a byte array from negative integers
 //    #[test]
 //    fn test_mixed_params_encoding() {
@@ -1,15 +1,21 @@
 use crate::{
-    crypto::SecretKey,
+    bytecode::{Bytecode, BytecodeVerifier},
+    call_params::CallParams,
+    contract_deploy::ContractDeploy,
+    gas::GasEstimator,
     id::{ContractId, FileId},
+    keystore::Keystore,
     proto::{
         CryptoService_grpc::CryptoServiceClient, FileService_grpc::FileServiceClient,
         SmartContractService_grpc::SmartContractServiceClient,
     },
     query::{
-        Query, QueryCryptoGetAccountBalance, QueryCryptoGetClaim, QueryCryptoGetInfo,
-        QueryFileGetContents, QueryFileGetInfo, QueryTransactionGetReceipt,
+        Query, QueryContractCall, QueryContractGetBytecode, QueryContractGetInfo,
+        QueryContractGetRecords, QueryCryptoGetAccountBalance, QueryCryptoGetClaim,
+        QueryCryptoGetInfo, QueryFileGetContents, QueryFileGetInfo, QueryTransactionGetReceipt,
         QueryTransactionGetRecord,
     },
+    signer::Signer,
     transaction::{
         Transaction, TransactionContractCall, TransactionContractCreate, TransactionContractUpdate,
         TransactionContractDelete, TransactionCryptoCreate, TransactionCryptoDelete,
@@ -18,23 +24,22 @@ use crate::{
     },
     AccountId, TransactionId,
 };
-use failure::{err_msg, format_err, Error};
+use failure::{format_err, Error};
 use grpc::ClientStub;
 use itertools::Itertools;
-use std::{fmt, sync::Arc, time::Duration};
-use try_from::TryInto;
+use std::{sync::Arc, time::Duration};
 
 pub struct ClientBuilder<'a> {
     address: &'a str,
     node: Option<AccountId>,
     operator: Option<AccountId>,
-    operator_secret: Option<Arc<dyn Fn() -> Result<SecretKey, Error> + Send + Sync>>,
+    operator_signer: Option<Arc<dyn Signer>>,
 }
 
 pub struct Client {
     pub(crate) node: Option<AccountId>,
     pub(crate) operator: Option<AccountId>,
-    pub(crate) operator_secret: Option<Arc<dyn Fn() -> Result<SecretKey, Error> + Send + Sync>>,
+    pub(crate) operator_signer: Option<Arc<dyn Signer>>,
     pub(crate) crypto: Arc<CryptoServiceClient>,
     pub(crate) file: Arc<FileServiceClient>,
     pub(crate) contract: Arc<SmartContractServiceClient>,
@@ -46,21 +51,28 @@ impl<'a> ClientBuilder<'a> {
         self
     }
 
-    pub fn operator<R, E>(
-        mut self,
-        operator: AccountId,
-        secret: impl Fn() -> R + Send + Sync + 'static,
-    ) -> Self
-    where
-        E: fmt::Debug + fmt::Display + Send + Sync + 'static,
-        R: TryInto<SecretKey, Err = E>,
-    {
+    /// Use `operator` as the operator account, signing with `signer`.
+    ///
+    /// `signer` may be a `SecretKey`, a remote `Signer`, or a function that returns the key,
+    /// such as `|| env::var("OPERATOR_SECRET")`; a function is called each time the operator
+    /// signs, and the key (or the string it is parsed from) is zeroed once it has been used.
+    pub fn operator(mut self, operator: AccountId, signer: impl Signer + 'static) -> Self {
         self.operator = Some(operator);
-        self.operator_secret = Some(Arc::new(move || secret().try_into().map_err(err_msg)));
+        self.operator_signer = Some(Arc::new(signer));
 
         self
     }
 
+    /// Use the key `name` of `keystore` as the operator; the account the key is tagged with
+    /// is the operator account.
+    pub fn operator_from_keystore(self, keystore: &Keystore, name: &str) -> Result<Self, Error> {
+        let key = keystore
+            .get(name)
+            .ok_or_else(|| format_err!("keystore: no key named {:?}", name))?;
+
+        Ok(self.operator(key.account, key.secret.clone()))
+    }
+
     pub fn build(self) -> Result<Client, Error> {
         let mut client = Client::new(&self.address)?;
 
@@ -68,9 +80,9 @@ impl<'a> ClientBuilder<'a> {
             client.set_node(node);
         }
 
-        if let (Some(operator), Some(secret)) = (self.operator, self.operator_secret) {
+        if let (Some(operator), Some(signer)) = (self.operator, self.operator_signer) {
             client.operator = Some(operator);
-            client.operator_secret = Some(secret);
+            client.operator_signer = Some(signer);
         }
 
         Ok(client)
@@ -83,7 +95,7 @@ impl Client {
             address,
             node: None,
             operator: None,
-            operator_secret: None,
+            operator_signer: None,
         }
     }
 
@@ -125,7 +137,7 @@ impl Client {
         Ok(Self {
             node,
             operator: None,
-            operator_secret: None,
+            operator_signer: None,
             crypto,
             file,
             contract,
@@ -138,16 +150,9 @@ impl Client {
     }
 
     #[inline]
-    pub fn set_operator<R, E>(
-        &mut self,
-        operator: AccountId,
-        secret: impl Fn() -> R + Send + Sync + 'static,
-    ) where
-        E: fmt::Debug + fmt::Display + Send + Sync + 'static,
-        R: TryInto<SecretKey, Err = E>,
-    {
+    pub fn set_operator(&mut self, operator: AccountId, signer: impl Signer + 'static) {
         self.operator = Some(operator);
-        self.operator_secret = Some(Arc::new(move || secret().try_into().map_err(err_msg)));
+        self.operator_signer = Some(Arc::new(signer));
     }
 
     #[inline]
@@ -180,6 +185,20 @@ impl Client {
         TransactionContractCreate::new(self)
     }
 
+    /// Deploy a smart contract from its compiled bytecode.
+    ///
+    /// The bytecode is uploaded to a new file, owned by the operator, before the contract
+    /// instance is created. The constructor parameters should be built with
+    /// `CallParams::new(None)` as a constructor has no function selector.
+    #[inline]
+    pub fn deploy_contract(
+        &self,
+        bytecode: impl Into<Vec<u8>>,
+        constructor_parameters: CallParams,
+    ) -> ContractDeploy<'_> {
+        ContractDeploy::new(self, bytecode.into(), constructor_parameters)
+    }
+
     #[inline]
     pub fn call_contract(&self, id: ContractId) -> Transaction<TransactionContractCall> {
         TransactionContractCall::new(self, id)
@@ -306,10 +325,65 @@ impl<'a> PartialContractMessage<'a> {
         TransactionContractCall::new(self.0, self.1)
     }
 
+    /// Call a function of the contract instance on the node that receives the query,
+    /// without reaching consensus. The call cannot change the state of the contract.
+    ///
+    /// Uses a default gas limit and maximum result size; build a `QueryContractCall`
+    /// directly to choose different values.
+    #[inline]
+    pub fn call_local(self, params: CallParams) -> Query<QueryContractCall> {
+        QueryContractCall::new(
+            self.0,
+            self.1,
+            QueryContractCall::DEFAULT_GAS,
+            params.assemble(),
+            QueryContractCall::DEFAULT_MAX_RESULT_SIZE,
+        )
+    }
+
+    /// Estimate the gas needed for a call of a function of the contract instance; see
+    /// `GasEstimator`.
+    #[inline]
+    pub fn estimate_gas(self, params: &CallParams) -> GasEstimator<'a> {
+        GasEstimator::new(self.0, self.1, params.assemble())
+    }
+
+    /// Get the runtime bytecode of the contract instance.
+    #[inline]
+    pub fn bytecode(self) -> Query<QueryContractGetBytecode> {
+        QueryContractGetBytecode::new(self.0, self.1)
+    }
+
+    /// Check that the contract instance runs the given compiled runtime bytecode, ignoring
+    /// placeholders and metadata; see `Bytecode::compare`.
+    #[inline]
+    pub fn verify_bytecode(self, expected: &'a Bytecode) -> BytecodeVerifier<'a> {
+        BytecodeVerifier::new(self.bytecode(), expected)
+    }
+
+    /// Get information about the contract instance.
+    #[inline]
+    pub fn info(self) -> Query<QueryContractGetInfo> {
+        QueryContractGetInfo::new(self.0, self.1)
+    }
+
+    /// Get the records of all the transactions against the contract instance
+    /// that were created during the last 25 hours.
+    #[inline]
+    pub fn records(self) -> Query<QueryContractGetRecords> {
+        QueryContractGetRecords::new(self.0, self.1)
+    }
+
     #[inline]
     pub fn update(self) -> Transaction<TransactionContractUpdate> {
         TransactionContractUpdate::new(self.0, self.1)
     }
+
+    /// Mark the contract instance as deleted, transferring its current hbars to another account.
+    #[inline]
+    pub fn delete(self) -> Transaction<TransactionContractDelete> {
+        TransactionContractDelete::new(self.0, self.1)
+    }
 }
 
 pub struct PartialTransactionMessage<'a>(&'a Client, TransactionId);
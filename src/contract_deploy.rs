@@ -0,0 +1,210 @@
+use crate::{
+    call_params::CallParams,
+    crypto::PublicKey,
+    gas::{self, DEFAULT_GAS_MARGIN},
+    Client, ContractId, ErrorKind, FileId, Status, TransactionId, TransactionReceipt,
+};
+use failure::{format_err, Error};
+use std::time::{Duration, Instant};
+use tokio::timer::delay;
+
+// Size of each chunk of hex-encoded bytecode sent in a single file create or append.
+// Keeps every transaction comfortably below the network's transaction size limit.
+const FILE_CHUNK_SIZE: usize = 2048;
+
+// How long the bytecode file lives if it is not deleted after the deployment.
+const FILE_EXPIRATION: Duration = Duration::from_secs(2_592_000);
+
+const RECEIPT_ATTEMPTS: usize = 10;
+
+/// Deploy a smart contract from its compiled bytecode.
+///
+/// The bytecode is uploaded to a new file (in chunks), the contract instance is created from
+/// that file with the encoded constructor parameters, and the file is optionally deleted
+/// afterwards. Every step waits for a successful receipt before moving on.
+pub struct ContractDeploy<'a> {
+    client: &'a Client,
+    bytecode: Vec<u8>,
+    constructor_parameters: CallParams,
+    gas: i64,
+    gas_auto: bool,
+    initial_balance: i64,
+    admin_key: Option<PublicKey>,
+    auto_renew_period: Option<Duration>,
+    delete_file: bool,
+}
+
+impl<'a> ContractDeploy<'a> {
+    pub(crate) fn new(
+        client: &'a Client,
+        bytecode: Vec<u8>,
+        constructor_parameters: CallParams,
+    ) -> Self {
+        Self {
+            client,
+            bytecode,
+            constructor_parameters,
+            gas: 0,
+            gas_auto: false,
+            initial_balance: 0,
+            admin_key: None,
+            auto_renew_period: None,
+            delete_file: false,
+        }
+    }
+
+    /// The maximum amount of gas to use for the constructor call.
+    #[inline]
+    pub fn gas(&mut self, gas: i64) -> &mut Self {
+        self.gas = gas;
+        self
+    }
+
+    /// Estimate the gas for the constructor call from the size of the bytecode instead; see
+    /// `gas::estimate_create_gas`.
+    #[inline]
+    pub fn gas_auto(&mut self) -> &mut Self {
+        self.gas_auto = true;
+        self
+    }
+
+    /// Number of tinybars to transfer to the new contract instance.
+    #[inline]
+    pub fn initial_balance(&mut self, balance: i64) -> &mut Self {
+        self.initial_balance = balance;
+        self
+    }
+
+    #[inline]
+    pub fn admin_key(&mut self, key: PublicKey) -> &mut Self {
+        self.admin_key = Some(key);
+        self
+    }
+
+    #[inline]
+    pub fn auto_renew_period(&mut self, period: Duration) -> &mut Self {
+        self.auto_renew_period = Some(period);
+        self
+    }
+
+    /// Should the bytecode file be deleted once the contract instance has been created?
+    #[inline]
+    pub fn delete_file(&mut self, delete: bool) -> &mut Self {
+        self.delete_file = delete;
+        self
+    }
+
+    pub async fn execute_async(&mut self) -> Result<ContractId, Error> {
+        let signer = match &self.client.operator_signer {
+            Some(signer) => signer.clone(),
+            None => Err(ErrorKind::MissingField("operator"))?,
+        };
+
+        // The network expects the file to contain the hex-encoded bytecode
+        let contents = hex::encode(&self.bytecode).into_bytes();
+        let mut chunks = contents.chunks(FILE_CHUNK_SIZE);
+
+        let id = self
+            .client
+            .create_file()
+            .expires_in(FILE_EXPIRATION)
+            .key(signer.public_key()?)
+            .contents(chunks.next().unwrap_or_default().to_vec())
+            .memo("[hedera-sdk-rust] deploy_contract")
+            .sign(&*signer) // sign as the owner of the file
+            .execute_async()
+            .await?;
+
+        let file: FileId = match wait_for_receipt(self.client, id).await?.file_id {
+            Some(file) => *file,
+            None => Err(format_err!("file create receipt did not contain a file ID"))?,
+        };
+
+        for chunk in chunks {
+            let id = self
+                .client
+                .append_file(file, chunk.to_vec())
+                .sign(&*signer)
+                .execute_async()
+                .await?;
+
+            wait_for_receipt(self.client, id).await?;
+        }
+
+        let constructor_parameters = self.constructor_parameters.assemble();
+
+        let gas = if self.gas_auto {
+            let params = &constructor_parameters;
+            gas::estimate_create_gas(&self.bytecode, params, DEFAULT_GAS_MARGIN).gas
+        } else {
+            self.gas
+        };
+
+        let mut tx = self.client.create_contract();
+
+        tx.file(file)
+            .gas(gas)
+            .initial_balance(self.initial_balance)
+            .constructor_parameters(constructor_parameters);
+
+        if let Some(key) = &self.admin_key {
+            tx.admin_key(key.clone());
+        }
+
+        if let Some(period) = self.auto_renew_period {
+            tx.auto_renew_period(period);
+        }
+
+        let id = tx.execute_async().await?;
+
+        let contract: ContractId = match wait_for_receipt(self.client, id).await?.contract_id {
+            Some(contract) => *contract,
+            None => Err(format_err!("contract create receipt did not contain a contract ID"))?,
+        };
+
+        if self.delete_file {
+            let id = self
+                .client
+                .file(file)
+                .delete()
+                .sign(&*signer)
+                .execute_async()
+                .await?;
+
+            wait_for_receipt(self.client, id).await?;
+        }
+
+        Ok(contract)
+    }
+
+    pub fn execute(&mut self) -> Result<ContractId, Error> {
+        crate::RUNTIME
+            .lock()
+            .block_on(self.execute_async())
+    }
+}
+
+async fn wait_for_receipt(client: &Client, id: TransactionId) -> Result<TransactionReceipt, Error> {
+    for attempt in 1..=RECEIPT_ATTEMPTS {
+        // If we got here we know we passed pre-check; give the network a moment
+        // to reach consensus before asking for the receipt, without blocking the runtime
+        delay(Instant::now() + Duration::from_secs(attempt as u64)).await;
+
+        let mut tx = client.transaction(id.clone()).receipt();
+        let receipt = tx.get_async().await?;
+
+        match receipt.status {
+            Status::Success => return Ok(receipt),
+
+            Status::Unknown => continue,
+
+            status => Err(format_err!(
+                "transaction {} has a non-successful status: {:?}",
+                id,
+                status
+            ))?,
+        }
+    }
+
+    Err(format_err!("timed out waiting for the receipt of transaction {}", id))
+}
@@ -0,0 +1,414 @@
+// ECDSA keys and signatures on the P-384 curve, with SHA-384
+use super::{decode_der, AlgorithmIdentifier, PrivateKeyInfo, SubjectPublicKeyInfo};
+use failure::{bail, format_err, Error};
+use num::BigUint;
+use once_cell::sync::Lazy;
+use ring::{
+    rand::SystemRandom,
+    signature::{
+        EcdsaKeyPair, UnparsedPublicKey, ECDSA_P384_SHA384_ASN1, ECDSA_P384_SHA384_ASN1_SIGNING,
+    },
+};
+use simple_asn1::{der_decode, der_encode, oid, to_der, ASN1Block, ASN1Class, OID};
+use std::fmt::{self, Debug, Display};
+use zeroize::{Zeroize, Zeroizing};
+
+// [https://tools.ietf.org/html/rfc5480#section-2.1.1]
+pub(super) static OID_EC_PUBLIC_KEY: Lazy<OID> = Lazy::new(|| { oid!(1, 2, 840, 10045, 2, 1) });
+
+// [https://tools.ietf.org/html/rfc5480#section-2.1.1.1]
+static OID_SECP384R1: Lazy<OID> = Lazy::new(|| { oid!(1, 3, 132, 0, 34) });
+
+/// Length of a P-384 secret key; a 384-bit scalar.
+pub const ECDSA_SECRET_KEY_LENGTH: usize = 48;
+
+/// Length of a P-384 public key; an uncompressed SEC1 point (`0x04 || x || y`).
+pub const ECDSA_PUBLIC_KEY_LENGTH: usize = 97;
+
+// [https://tools.ietf.org/html/rfc5915#section-3]
+// ECPrivateKey ::= SEQUENCE {
+//      version        INTEGER { ecPrivkeyVer1(1) },
+//      privateKey     OCTET STRING,
+//      parameters [0] ECParameters {{ NamedCurve }} OPTIONAL,
+//      publicKey  [1] BIT STRING OPTIONAL }
+
+fn curve_algorithm() -> AlgorithmIdentifier {
+    AlgorithmIdentifier {
+        algorithm: OID_EC_PUBLIC_KEY.clone(),
+        parameters: Some(ASN1Block::ObjectIdentifier(0, OID_SECP384R1.clone())),
+    }
+}
+
+fn check_curve(algorithm: &AlgorithmIdentifier) -> Result<(), Error> {
+    match &algorithm.parameters {
+        Some(ASN1Block::ObjectIdentifier(_, curve)) if *curve == *OID_SECP384R1 => Ok(()),
+        parameters => bail!("ecdsa: unsupported curve: {:?}", parameters),
+    }
+}
+
+// The block tagged [`tag`] in a SEQUENCE, if any; depending on the ASN.1 lib, the block is
+// either decoded or left as its encoded bytes
+fn tagged(blocks: &[ASN1Block], tag: u8) -> Result<Option<ASN1Block>, Error> {
+    let tag = BigUint::from(tag);
+
+    for block in blocks {
+        match block {
+            ASN1Block::Explicit(ASN1Class::ContextSpecific, _, t, inner) if *t == tag => {
+                return Ok(Some((**inner).clone()));
+            }
+
+            ASN1Block::Unknown(ASN1Class::ContextSpecific, _, _, t, body) if *t == tag => {
+                return Ok(decode_der(body)?.into_iter().next());
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(None)
+}
+
+/// An ECDSA public key on the P-384 curve.
+#[derive(PartialEq, Clone)]
+pub struct EcdsaPublicKey(Vec<u8>);
+
+impl EcdsaPublicKey {
+    /// Construct an `EcdsaPublicKey` from a slice of bytes.
+    /// Bytes are expected to be either an uncompressed SEC1 point or encoded in ASN.1.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let bytes = bytes.as_ref();
+
+        if bytes.len() == ECDSA_PUBLIC_KEY_LENGTH && bytes[0] == 0x04 {
+            return Ok(EcdsaPublicKey(bytes.to_vec()));
+        }
+
+        Self::from_info(&der_decode(&bytes)?)
+    }
+
+    pub(super) fn from_info(info: &SubjectPublicKeyInfo) -> Result<Self, Error> {
+        if info.algorithm.algorithm != *OID_EC_PUBLIC_KEY {
+            bail!(
+                "ecdsa: unknown public key algorithm: {:?}",
+                info.algorithm.algorithm
+            );
+        }
+
+        check_curve(&info.algorithm)?;
+
+        let point = &info.subject_public_key;
+
+        if point.len() != ECDSA_PUBLIC_KEY_LENGTH || point[0] != 0x04 {
+            bail!("ecdsa: expected an uncompressed P-384 point");
+        }
+
+        Ok(EcdsaPublicKey(point.clone()))
+    }
+
+    /// Return the `EcdsaPublicKey` as an uncompressed SEC1 point.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Format an `EcdsaPublicKey` as a vec of bytes in ASN.1 format.
+    pub fn to_encoded_bytes(&self) -> Vec<u8> {
+        der_encode(&SubjectPublicKeyInfo {
+            algorithm: curve_algorithm(),
+            subject_public_key: self.0.clone(),
+        })
+        // NOTE: Not possible to fail. Only fail case the library has is if OIDs are
+        //       given incorrectly.
+        .unwrap()
+    }
+
+    /// Verify a signature on a message with this `EcdsaPublicKey`.
+    ///
+    /// The message is hashed with SHA-384.
+    pub fn verify(
+        &self,
+        message: impl AsRef<[u8]>,
+        signature: &EcdsaSignature,
+    ) -> Result<bool, Error> {
+        let key = UnparsedPublicKey::new(&ECDSA_P384_SHA384_ASN1, &self.0);
+
+        // NOTE: ring does not tell an invalid key apart from a signature that does not match
+        Ok(key.verify(message.as_ref(), &signature.0).is_ok())
+    }
+}
+
+impl Debug for EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\"", self)
+    }
+}
+
+/// Format an `EcdsaPublicKey` as a hex representation of its bytes in ASN.1 format.
+impl Display for EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.to_encoded_bytes()))
+    }
+}
+
+/// An ECDSA secret key on the P-384 curve.
+///
+/// Our copy of the scalar is zeroed on drop, but the key pair `ring` signs with keeps its own
+/// copy for the lifetime of the key and does not zero it. The `Debug` output only shows the
+/// public key.
+pub struct EcdsaSecretKey {
+    secret: Vec<u8>,
+    public: EcdsaPublicKey,
+    key_pair: EcdsaKeyPair,
+}
+
+impl EcdsaSecretKey {
+    /// Generate an `EcdsaSecretKey` using a cryptographically secure random number generator.
+    pub fn generate() -> Result<Self, Error> {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P384_SHA384_ASN1_SIGNING, &rng)
+            .map_err(|e| format_err!("ecdsa: failed to generate a key: {}", e))?;
+
+        Self::from_bytes(pkcs8.as_ref())
+    }
+
+    /// Construct an `EcdsaSecretKey` from a slice of bytes.
+    /// Bytes are expected to be an `ECPrivateKey` (SEC1) or `PrivateKeyInfo` (PKCS#8) in ASN.1.
+    ///
+    /// The public point is not derived from the scalar, so the `ECPrivateKey` must include
+    /// its optional `[1] publicKey` field.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let bytes = bytes.as_ref();
+
+        match der_decode::<PrivateKeyInfo>(bytes) {
+            Ok(info) => Self::from_info(&info),
+            Err(_) => Self::from_ec_private_key(bytes),
+        }
+    }
+
+    pub(super) fn from_info(info: &PrivateKeyInfo) -> Result<Self, Error> {
+        if info.algorithm.algorithm != *OID_EC_PUBLIC_KEY {
+            bail!(
+                "ecdsa: PKCS#8 wrapping contained private key with unknown algorithm: {:?}",
+                info.algorithm.algorithm
+            );
+        }
+
+        check_curve(&info.algorithm)?;
+
+        Self::from_ec_private_key(&info.private_key)
+    }
+
+    fn from_ec_private_key(der: &[u8]) -> Result<Self, Error> {
+        let blocks = match decode_der(der)?.into_iter().next() {
+            Some(ASN1Block::Sequence(_, blocks)) => blocks,
+            // Never print the block; it may hold the secret scalar
+            _ => bail!("ecdsa: expected `ECPrivateKey` to be a `SEQUENCE`"),
+        };
+
+        let secret = match blocks.get(1) {
+            Some(ASN1Block::OctetString(_, secret)) if secret.len() <= ECDSA_SECRET_KEY_LENGTH => {
+                // The scalar should be fixed-length but some encoders strip leading zeros
+                let mut padded = vec![0; ECDSA_SECRET_KEY_LENGTH - secret.len()];
+                padded.extend(secret);
+                Zeroizing::new(padded)
+            }
+
+            Some(ASN1Block::OctetString(_, secret)) => bail!(
+                "ecdsa: expected a P-384 private key of at most {} bytes; found {} bytes",
+                ECDSA_SECRET_KEY_LENGTH,
+                secret.len()
+            ),
+
+            _ => bail!("ecdsa: expected `ECPrivateKey` to hold an `OCTET STRING` private key"),
+        };
+
+        if let Some(ASN1Block::ObjectIdentifier(_, curve)) = tagged(&blocks[2..], 0)? {
+            if curve != *OID_SECP384R1 {
+                bail!("ecdsa: unsupported curve: {:?}", curve);
+            }
+        }
+
+        let public = match tagged(&blocks[2..], 1)? {
+            Some(ASN1Block::BitString(_, _, point)) => EcdsaPublicKey::from_bytes(point)?,
+            _ => bail!(
+                "ecdsa: an `ECPrivateKey` without its optional `[1] publicKey` is not supported"
+            ),
+        };
+
+        Self::from_parts(&secret, public)
+    }
+
+    fn from_parts(secret: &[u8], public: EcdsaPublicKey) -> Result<Self, Error> {
+        let key_pair = EcdsaKeyPair::from_private_key_and_public_key(
+            &ECDSA_P384_SHA384_ASN1_SIGNING,
+            secret,
+            public.as_bytes(),
+        )
+        .map_err(|e| format_err!("ecdsa: invalid key pair: {}", e))?;
+
+        Ok(EcdsaSecretKey {
+            secret: secret.to_vec(),
+            public,
+            key_pair,
+        })
+    }
+
+    /// Return the `EcdsaSecretKey` as a raw scalar; this exposes the secret.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.secret
+    }
+
+    /// Format an `EcdsaSecretKey` as a vec of bytes in ASN.1 (PKCS#8) format; this exposes the
+    /// secret.
+    pub fn to_encoded_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let public = ASN1Block::BitString(0, self.public.0.len() * 8, self.public.0.clone());
+
+        let ec_private_key = ASN1Block::Sequence(
+            0,
+            vec![
+                ASN1Block::Integer(0, 1.into()),
+                ASN1Block::OctetString(0, self.secret.clone()),
+                ASN1Block::Explicit(ASN1Class::ContextSpecific, 0, 1u8.into(), Box::new(public)),
+            ],
+        );
+
+        Zeroizing::new(
+            der_encode(&PrivateKeyInfo {
+                algorithm: curve_algorithm(),
+                // NOTE: Not possible to fail; the blocks are built above
+                private_key: to_der(&ec_private_key).unwrap(),
+            })
+            // NOTE: Not possible to fail. Only fail case the library has is if OIDs are
+            //       given incorrectly.
+            .unwrap(),
+        )
+    }
+
+    /// Derive an `EcdsaPublicKey` from this `EcdsaSecretKey`.
+    #[inline]
+    pub fn public(&self) -> EcdsaPublicKey {
+        self.public.clone()
+    }
+
+    /// Sign a message with this `EcdsaSecretKey`.
+    ///
+    /// The message is hashed with SHA-384 and the signature is encoded in ASN.1.
+    pub fn sign(&self, message: impl AsRef<[u8]>) -> EcdsaSignature {
+        let signature = self
+            .key_pair
+            .sign(&SystemRandom::new(), message.as_ref())
+            // NOTE: Only fails if the system random number generator does
+            .expect("ecdsa: failed to sign");
+
+        EcdsaSignature(signature.as_ref().to_vec())
+    }
+}
+
+impl Clone for EcdsaSecretKey {
+    #[inline]
+    fn clone(&self) -> Self {
+        // NOTE: Not possible to fail; the key pair was already checked
+        Self::from_parts(&self.secret, self.public.clone()).unwrap()
+    }
+}
+
+impl Drop for EcdsaSecretKey {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl Debug for EcdsaSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EcdsaSecretKey").field("public", &self.public).finish()
+    }
+}
+
+/// An ECDSA signature, encoded in ASN.1 as `SEQUENCE { r INTEGER, s INTEGER }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EcdsaSignature(Vec<u8>);
+
+impl EcdsaSignature {
+    /// Construct an `EcdsaSignature` from a slice of bytes in ASN.1 format.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let bytes = bytes.as_ref();
+
+        match decode_der(bytes)?.as_slice() {
+            [ASN1Block::Sequence(_, values)] => match values.as_slice() {
+                [ASN1Block::Integer(..), ASN1Block::Integer(..)] => {}
+                _ => bail!("ecdsa: expected a signature of two INTEGERs"),
+            },
+
+            _ => bail!("ecdsa: expected a signature SEQUENCE"),
+        }
+
+        Ok(EcdsaSignature(bytes.to_vec()))
+    }
+
+    /// Return the `EcdsaSignature` as bytes in ASN.1 format.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Format an `EcdsaSignature` as a hex representation of its bytes.
+impl Display for EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EcdsaPublicKey, EcdsaSecretKey, EcdsaSignature};
+    use failure::Error;
+
+    const MESSAGE: &str = "This is a message about the world.";
+
+    #[test]
+    fn test_sign_verify() -> Result<(), Error> {
+        let key = EcdsaSecretKey::generate()?;
+        let signature = key.sign(MESSAGE.as_bytes());
+
+        assert!(key.public().verify(MESSAGE.as_bytes(), &signature)?);
+        assert!(!key.public().verify(b"This is another message.", &signature)?);
+
+        let signature = EcdsaSignature::from_bytes(signature.as_bytes())?;
+        assert!(key.public().verify(MESSAGE.as_bytes(), &signature)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoding() -> Result<(), Error> {
+        let key1 = EcdsaSecretKey::generate()?;
+        let key2 = EcdsaSecretKey::from_bytes(&*key1.to_encoded_bytes())?;
+
+        assert_eq!(key1.as_bytes(), key2.as_bytes());
+        assert_eq!(key1.public(), key2.public());
+
+        let public = EcdsaPublicKey::from_bytes(key1.public().to_encoded_bytes())?;
+        assert_eq!(public, key1.public());
+        assert_eq!(EcdsaPublicKey::from_bytes(public.as_bytes())?, public);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // `ECPrivateKey ::= SEQUENCE { version 1, privateKey OCTET STRING }`
+        let mut der = vec![0x30, 0x36, 0x02, 0x01, 0x01, 0x04, 0x31];
+        der.extend(&[0xab; 49]);
+
+        let err = EcdsaSecretKey::from_bytes(&der).err().unwrap().to_string();
+        assert!(err.contains("found 49 bytes"));
+        assert!(!err.contains("171"));
+
+        let mut der = vec![0x30, 0x35, 0x02, 0x01, 0x01, 0x04, 0x30];
+        der.extend(&[0x01; 48]);
+
+        let err = EcdsaSecretKey::from_bytes(&der).err().unwrap().to_string();
+        assert!(err.contains("`[1] publicKey`"));
+    }
+}
@@ -0,0 +1,241 @@
+// The 22-word mnemonics of the first Hedera wallets, which predate BIP-39 in Hedera
+use failure::{bail, format_err, Error};
+use num::{BigUint, Zero};
+use ring::pbkdf2;
+use std::{fs, num::NonZeroU32, path::Path, str::FromStr};
+use zeroize::{Zeroize, Zeroizing};
+
+// 22 words of 12 bits is 264 bits; 32 bytes of entropy and a checksum byte
+const LEGACY_MNEMONIC_LENGTH: usize = 22;
+const WORD_LIST_LENGTH: usize = 4096;
+const DATA_LENGTH: usize = 33;
+
+const DERIVE_ITERATIONS: u32 = 2048;
+const DERIVE_SALT: &[u8] = &[0xff];
+
+/// The scheme of a mnemonic phrase, as told by its number of words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MnemonicScheme {
+    /// A BIP-39 phrase of 12, 15, 18, 21 or 24 words.
+    Bip39,
+
+    /// A legacy Hedera phrase of 22 words.
+    Legacy,
+}
+
+impl MnemonicScheme {
+    /// Detect the scheme of `phrase`; no BIP-39 phrase has 22 words, so the count decides.
+    pub fn detect(phrase: &str) -> Result<Self, Error> {
+        match phrase.split_whitespace().count() {
+            12 | 15 | 18 | 21 | 24 => Ok(MnemonicScheme::Bip39),
+            LEGACY_MNEMONIC_LENGTH => Ok(MnemonicScheme::Legacy),
+
+            count => bail!(
+                "mnemonic: expected 12, 15, 18, 21 or 24 words, or 22 for a legacy phrase; \
+                 found {}",
+                count
+            ),
+        }
+    }
+}
+
+/// The word list of legacy Hedera mnemonics; 4096 distinct words, in order.
+///
+/// The list is published with the Hedera SDKs and is not bundled; read it from a file of the
+/// words separated by whitespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyWordList(Vec<String>);
+
+impl LegacyWordList {
+    /// Construct a `LegacyWordList` from its words, in order.
+    pub fn new(words: Vec<String>) -> Result<Self, Error> {
+        if words.len() != WORD_LIST_LENGTH {
+            bail!(
+                "legacy word list: expected {} words; found {}",
+                WORD_LIST_LENGTH,
+                words.len()
+            );
+        }
+
+        let words: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+
+        let mut sorted: Vec<&str> = words.iter().map(String::as_str).collect();
+        sorted.sort();
+
+        if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+            bail!("legacy word list: duplicate word: {:?}", pair[0]);
+        }
+
+        Ok(LegacyWordList(words))
+    }
+
+    /// Read a `LegacyWordList` from the file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+
+        fs::read_to_string(path)
+            .map_err(|e| format_err!("legacy word list: failed to read {:?}: {}", path, e))?
+            .parse()
+    }
+
+    fn index(&self, word: &str) -> Result<usize, Error> {
+        let word = Zeroizing::new(word.to_lowercase());
+
+        self.0.iter().position(|w| *w == *word).ok_or_else(|| {
+            format_err!("legacy mnemonic: word is not in the word list: {:?}", *word)
+        })
+    }
+}
+
+impl FromStr for LegacyWordList {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Self::new(s.split_whitespace().map(str::to_string).collect())
+    }
+}
+
+// Decode the 32 bytes of entropy of a legacy phrase
+pub(super) fn entropy(phrase: &str, words: &LegacyWordList) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let indices = phrase
+        .split_whitespace()
+        .map(|word| words.index(word))
+        .collect::<Result<Vec<usize>, Error>>()?;
+
+    if indices.len() != LEGACY_MNEMONIC_LENGTH {
+        bail!(
+            "legacy mnemonic: expected {} words; found {}",
+            LEGACY_MNEMONIC_LENGTH,
+            indices.len()
+        );
+    }
+
+    // The words are the digits of a base 4096 number; the bytes are the same number in base 256
+    let number = indices.iter().fold(BigUint::zero(), |number, &index| {
+        number * WORD_LIST_LENGTH + index
+    });
+
+    let mut bytes = Zeroizing::new(number.to_bytes_be());
+
+    let mut data = Zeroizing::new(vec![0; DATA_LENGTH - bytes.len()]);
+    data.extend_from_slice(&bytes);
+    bytes.zeroize();
+
+    // The entropy is masked with its checksum
+    let checksum = data.pop().unwrap();
+    for byte in data.iter_mut() {
+        *byte ^= checksum;
+    }
+
+    if crc8(&data) != checksum {
+        bail!("legacy mnemonic: checksum mismatch");
+    }
+
+    Ok(data)
+}
+
+// Derive the ed25519 secret key at `index` from the entropy of a legacy phrase
+pub(super) fn derive(entropy: &[u8], index: i64) -> [u8; 32] {
+    let mut password = Zeroizing::new(Vec::with_capacity(entropy.len() + 8));
+    password.extend_from_slice(entropy);
+    password.extend_from_slice(&index.to_be_bytes());
+
+    let mut key = [0; 32];
+
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA512,
+        // NOTE: Not possible to fail; the count is a non-zero constant
+        NonZeroU32::new(DERIVE_ITERATIONS).unwrap(),
+        DERIVE_SALT,
+        &password,
+        &mut key,
+    );
+
+    key
+}
+
+// CRC-8 with the reflected polynomial 0xb2; the last byte is left out, as the first
+// Hedera wallets did
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0xff;
+
+    for &byte in &data[..data.len() - 1] {
+        crc ^= byte;
+
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ if crc & 1 == 0 { 0 } else { 0xb2 };
+        }
+    }
+
+    crc ^ 0xff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::ToPrimitive;
+
+    fn word_list() -> LegacyWordList {
+        LegacyWordList::new((0..WORD_LIST_LENGTH).map(|i| format!("word{}", i)).collect()).unwrap()
+    }
+
+    // The inverse of `entropy`
+    fn phrase(entropy: &[u8], words: &LegacyWordList) -> String {
+        let checksum = crc8(entropy);
+
+        let mut data: Vec<u8> = entropy.iter().map(|byte| byte ^ checksum).collect();
+        data.push(checksum);
+
+        let mut number = BigUint::from_bytes_be(&data);
+        let mut phrase = Vec::new();
+
+        for _ in 0..LEGACY_MNEMONIC_LENGTH {
+            let index = (number.clone() % WORD_LIST_LENGTH).to_usize().unwrap();
+            phrase.push(words.0[index].clone());
+            number = number / WORD_LIST_LENGTH;
+        }
+
+        phrase.reverse();
+        phrase.join(" ")
+    }
+
+    #[test]
+    fn test_entropy() -> Result<(), Error> {
+        let words = word_list();
+        let entropy_bytes: Vec<u8> = (0..32).collect();
+        let phrase = phrase(&entropy_bytes, &words);
+
+        assert_eq!(MnemonicScheme::detect(&phrase)?, MnemonicScheme::Legacy);
+        assert_eq!(*entropy(&phrase, &words)?, entropy_bytes);
+        assert_eq!(*entropy(&phrase.to_uppercase(), &words)?, entropy_bytes);
+
+        // Changing a word breaks the checksum
+        let altered = phrase.replacen("word", "word1", 1);
+        assert!(entropy(&altered, &words).is_err());
+
+        assert!(entropy("word1 word2", &words).is_err());
+        assert!(entropy(&phrase.replacen("word", "other", 1), &words).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect() -> Result<(), Error> {
+        let bip39 = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                     abandon abandon about";
+
+        assert_eq!(MnemonicScheme::detect(bip39)?, MnemonicScheme::Bip39);
+        assert!(MnemonicScheme::detect("abandon about").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_derive() {
+        let entropy = [7; 32];
+
+        assert_eq!(derive(&entropy, 0), derive(&entropy, 0));
+        assert_ne!(derive(&entropy, 0), derive(&entropy, 1));
+        assert_ne!(derive(&entropy, -1), derive(&entropy, 0xff_ffff_ffff));
+    }
+}
@@ -0,0 +1,276 @@
+// BIP-39 mnemonic phrases, in any of the languages of BIP-39
+// [https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki]
+use super::{DerivationPath, SecretKey};
+use crate::error::MnemonicError;
+use bip39::{Language, MnemonicType, Seed};
+use failure::Error;
+use once_cell::sync::OnceCell;
+use std::{
+    fmt::{self, Debug},
+    str::FromStr,
+};
+use zeroize::Zeroizing;
+
+const WORD_LIST_LENGTH: usize = 2048;
+
+/// A language of the BIP-39 word lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MnemonicLanguage {
+    English,
+    ChineseSimplified,
+    ChineseTraditional,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Spanish,
+}
+
+impl Default for MnemonicLanguage {
+    fn default() -> Self {
+        MnemonicLanguage::English
+    }
+}
+
+impl MnemonicLanguage {
+    fn to_bip39(self) -> Language {
+        match self {
+            MnemonicLanguage::English => Language::English,
+            MnemonicLanguage::ChineseSimplified => Language::ChineseSimplified,
+            MnemonicLanguage::ChineseTraditional => Language::ChineseTraditional,
+            MnemonicLanguage::French => Language::French,
+            MnemonicLanguage::Italian => Language::Italian,
+            MnemonicLanguage::Japanese => Language::Japanese,
+            MnemonicLanguage::Korean => Language::Korean,
+            MnemonicLanguage::Spanish => Language::Spanish,
+        }
+    }
+
+    // The word list, in order; built once per language from phrases of chosen entropy, as the
+    // word lists of `bip39` are not public
+    fn words(self) -> &'static [String] {
+        static WORD_LISTS: [OnceCell<Vec<String>>; 8] = [
+            OnceCell::new(),
+            OnceCell::new(),
+            OnceCell::new(),
+            OnceCell::new(),
+            OnceCell::new(),
+            OnceCell::new(),
+            OnceCell::new(),
+            OnceCell::new(),
+        ];
+
+        WORD_LISTS[self as usize].get_or_init(|| {
+            (0..WORD_LIST_LENGTH)
+                .map(|index| {
+                    // The first word of a phrase is the first 11 bits of its entropy
+                    let mut entropy = [0; 16];
+                    entropy[0] = (index >> 3) as u8;
+                    entropy[1] = ((index & 0b111) << 5) as u8;
+
+                    // NOTE: Not possible to fail; the entropy is of a valid length
+                    let mnemonic =
+                        bip39::Mnemonic::from_entropy(&entropy, self.to_bip39()).unwrap();
+
+                    mnemonic.phrase().split_whitespace().next().unwrap().to_string()
+                })
+                .collect()
+        })
+    }
+}
+
+/// A BIP-39 mnemonic phrase of 12, 15, 18, 21 or 24 words.
+///
+/// The phrase is zeroed on drop, and the `Debug` output only shows its language and length.
+#[derive(Clone)]
+pub struct Mnemonic {
+    phrase: Zeroizing<String>,
+    language: MnemonicLanguage,
+}
+
+impl Mnemonic {
+    /// Generate a `Mnemonic` of `word_count` words in `language`, using a cryptographically
+    /// secure random number generator.
+    pub fn generate(word_count: usize, language: MnemonicLanguage) -> Result<Self, MnemonicError> {
+        let mnemonic = bip39::Mnemonic::new(mnemonic_type(word_count)?, language.to_bip39());
+
+        // Parsed again to separate the words as `from_phrase` does
+        Self::from_phrase(&Zeroizing::new(mnemonic.into_phrase()), language)
+    }
+
+    /// Construct a `Mnemonic` from a phrase in `language`; the words and the checksum are
+    /// validated.
+    pub fn from_phrase(phrase: &str, language: MnemonicLanguage) -> Result<Self, MnemonicError> {
+        let words: Vec<Zeroizing<String>> =
+            phrase.split_whitespace().map(|word| Zeroizing::new(word.to_lowercase())).collect();
+
+        mnemonic_type(words.len())?;
+
+        let list = language.words();
+
+        for (index, word) in words.iter().enumerate() {
+            if !list.iter().any(|w| *w == **word) {
+                return Err(MnemonicError::UnknownWord { index, word: (**word).clone() });
+            }
+        }
+
+        let mut phrase = Zeroizing::new(String::with_capacity(phrase.len()));
+
+        for (index, word) in words.iter().enumerate() {
+            if index > 0 {
+                phrase.push(' ');
+            }
+
+            phrase.push_str(word);
+        }
+
+        // The words are known and of a valid count, so only the checksum can fail
+        if bip39::Mnemonic::from_phrase(&phrase, language.to_bip39()).is_err() {
+            return Err(MnemonicError::Checksum);
+        }
+
+        Ok(Self { phrase, language })
+    }
+
+    /// The phrase, with its words separated by single spaces; this exposes the secret.
+    #[inline]
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+
+    /// The words of the phrase.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.phrase.split(' ')
+    }
+
+    /// The number of words of the phrase.
+    pub fn word_count(&self) -> usize {
+        self.words().count()
+    }
+
+    #[inline]
+    pub fn language(&self) -> MnemonicLanguage {
+        self.language
+    }
+
+    /// The BIP-39 seed of the phrase with `passphrase`; an empty passphrase is no passphrase.
+    pub fn to_seed(&self, passphrase: &str) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(Seed::new(&self.to_bip39(), passphrase).as_bytes().to_vec())
+    }
+
+    /// Derive the ed25519 `SecretKey` at `path` with SLIP-10, from the seed of the phrase
+    /// with `passphrase`; see `DerivationPath::hedera` for the path of each account key.
+    pub fn to_secret_key(&self, passphrase: &str, path: &DerivationPath) -> SecretKey {
+        SecretKey::from_seed_with_path(&self.to_seed(passphrase), path)
+    }
+
+    fn to_bip39(&self) -> bip39::Mnemonic {
+        // NOTE: Not possible to fail; the phrase was validated when this was constructed
+        bip39::Mnemonic::from_phrase(&self.phrase, self.language.to_bip39()).unwrap()
+    }
+}
+
+/// Construct an English `Mnemonic` from its phrase.
+impl FromStr for Mnemonic {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(Self::from_phrase(s, MnemonicLanguage::English)?)
+    }
+}
+
+impl Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mnemonic")
+            .field("language", &self.language)
+            .field("word_count", &self.word_count())
+            .finish()
+    }
+}
+
+fn mnemonic_type(word_count: usize) -> Result<MnemonicType, MnemonicError> {
+    match word_count {
+        12 => Ok(MnemonicType::Words12),
+        15 => Ok(MnemonicType::Words15),
+        18 => Ok(MnemonicType::Words18),
+        21 => Ok(MnemonicType::Words21),
+        24 => Ok(MnemonicType::Words24),
+        _ => Err(MnemonicError::WordCount(word_count)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                          abandon abandon about";
+
+    #[test]
+    fn test_generate() -> Result<(), Error> {
+        for &word_count in &[12, 15, 18, 21, 24] {
+            let mnemonic = Mnemonic::generate(word_count, MnemonicLanguage::Japanese)?;
+            let parsed = Mnemonic::from_phrase(mnemonic.phrase(), MnemonicLanguage::Japanese)?;
+
+            assert_eq!(mnemonic.word_count(), word_count);
+            assert_eq!(parsed.phrase(), mnemonic.phrase());
+        }
+
+        assert_eq!(
+            Mnemonic::generate(13, MnemonicLanguage::English).unwrap_err(),
+            MnemonicError::WordCount(13)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_phrase() -> Result<(), Error> {
+        let mnemonic: Mnemonic = PHRASE.to_uppercase().parse()?;
+        assert_eq!(mnemonic.phrase(), PHRASE);
+        assert_eq!(mnemonic.word_count(), 12);
+
+        assert_eq!(
+            Mnemonic::from_phrase(&PHRASE.replace("about", "abuot"), MnemonicLanguage::English)
+                .unwrap_err(),
+            MnemonicError::UnknownWord { index: 11, word: "abuot".to_string() }
+        );
+
+        assert_eq!(
+            Mnemonic::from_phrase(&PHRASE.replace("about", "above"), MnemonicLanguage::English)
+                .unwrap_err(),
+            MnemonicError::Checksum
+        );
+
+        assert!(Mnemonic::from_phrase(PHRASE, MnemonicLanguage::French).is_err());
+        assert!(!format!("{:?}", mnemonic).contains("abandon"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_secret_key() -> Result<(), Error> {
+        let mnemonic: Mnemonic = PHRASE.parse()?;
+
+        // [https://github.com/trezor/python-mnemonic/blob/master/vectors.json]
+        assert_eq!(
+            hex::encode(&*mnemonic.to_seed("TREZOR")),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e\
+             1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+
+        let key = mnemonic.to_secret_key("", &DerivationPath::hedera(0)?);
+        assert_eq!(
+            hex::encode(key.as_bytes()),
+            "523f9ff611ac02e8e188617750e7e50feabd2ef9ee9b218a5c8ce2693275361d"
+        );
+
+        assert_ne!(
+            mnemonic.to_secret_key("passphrase", &DerivationPath::hedera(0)?).as_bytes(),
+            key.as_bytes()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,458 @@
+// Password-based encryption of private keys; PBES2 with PBKDF2 and AES
+// [https://tools.ietf.org/html/rfc8018#section-6.2]
+use super::{decode_der, ASN1Error, AlgorithmIdentifier};
+use aes::{Aes128, Aes256};
+use block_modes::{block_padding::Pkcs7, BlockMode, Cbc};
+use failure::{bail, format_err, Error};
+use num::{BigInt, ToPrimitive};
+use once_cell::sync::Lazy;
+use ring::{
+    aead::{self, Aad, LessSafeKey, Nonce, UnboundKey},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use simple_asn1::{oid, to_der, ASN1Block, FromASN1, OID};
+use std::num::NonZeroU32;
+use zeroize::Zeroizing;
+
+// [https://tools.ietf.org/html/rfc8018#appendix-A.4]
+static OID_PBES2: Lazy<OID> = Lazy::new(|| { oid!(1, 2, 840, 113549, 1, 5, 13) });
+
+// [https://tools.ietf.org/html/rfc8018#appendix-A.2]
+static OID_PBKDF2: Lazy<OID> = Lazy::new(|| { oid!(1, 2, 840, 113549, 1, 5, 12) });
+
+// [https://tools.ietf.org/html/rfc8018#appendix-B.1]
+static OID_HMAC_WITH_SHA1: Lazy<OID> = Lazy::new(|| { oid!(1, 2, 840, 113549, 2, 7) });
+static OID_HMAC_WITH_SHA256: Lazy<OID> = Lazy::new(|| { oid!(1, 2, 840, 113549, 2, 9) });
+static OID_HMAC_WITH_SHA384: Lazy<OID> = Lazy::new(|| { oid!(1, 2, 840, 113549, 2, 10) });
+static OID_HMAC_WITH_SHA512: Lazy<OID> = Lazy::new(|| { oid!(1, 2, 840, 113549, 2, 11) });
+
+// [https://tools.ietf.org/html/rfc3565#section-4.1]
+// [https://tools.ietf.org/html/rfc5084#section-3.2]
+static OID_AES128_CBC: Lazy<OID> = Lazy::new(|| { oid!(2, 16, 840, 1, 101, 3, 4, 1, 2) });
+static OID_AES256_CBC: Lazy<OID> = Lazy::new(|| { oid!(2, 16, 840, 1, 101, 3, 4, 1, 42) });
+static OID_AES128_GCM: Lazy<OID> = Lazy::new(|| { oid!(2, 16, 840, 1, 101, 3, 4, 1, 6) });
+static OID_AES256_GCM: Lazy<OID> = Lazy::new(|| { oid!(2, 16, 840, 1, 101, 3, 4, 1, 46) });
+
+// Iterations of PBKDF2 for newly encrypted keys
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+// The count is read from the encrypted key, so it is bounded before deriving; 10 million
+// iterations take several seconds
+const PBKDF2_MAX_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LENGTH: usize = 16;
+const CBC_IV_LENGTH: usize = 16;
+const GCM_NONCE_LENGTH: usize = 12;
+const GCM_TAG_LENGTH: usize = 16;
+
+/// A cipher to encrypt a secret key with; see `SecretKey::to_encrypted_bytes`.
+///
+/// The key of the cipher is derived from the passphrase with PBKDF2 (HMAC-SHA256).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionScheme {
+    Aes128Cbc,
+    Aes256Cbc,
+    Aes128Gcm,
+    Aes256Gcm,
+}
+
+impl Default for EncryptionScheme {
+    fn default() -> Self {
+        EncryptionScheme::Aes256Cbc
+    }
+}
+
+impl EncryptionScheme {
+    fn oid(self) -> &'static OID {
+        match self {
+            EncryptionScheme::Aes128Cbc => &OID_AES128_CBC,
+            EncryptionScheme::Aes256Cbc => &OID_AES256_CBC,
+            EncryptionScheme::Aes128Gcm => &OID_AES128_GCM,
+            EncryptionScheme::Aes256Gcm => &OID_AES256_GCM,
+        }
+    }
+
+    fn key_length(self) -> usize {
+        match self {
+            EncryptionScheme::Aes128Cbc | EncryptionScheme::Aes128Gcm => 16,
+            EncryptionScheme::Aes256Cbc | EncryptionScheme::Aes256Gcm => 32,
+        }
+    }
+
+    fn iv_length(self) -> usize {
+        match self {
+            EncryptionScheme::Aes128Cbc | EncryptionScheme::Aes256Cbc => CBC_IV_LENGTH,
+            EncryptionScheme::Aes128Gcm | EncryptionScheme::Aes256Gcm => GCM_NONCE_LENGTH,
+        }
+    }
+
+    // The scheme and its IV (or nonce) from the `encryptionScheme` of the PBES2 parameters
+    fn from_algorithm(algorithm: &AlgorithmIdentifier) -> Result<(Self, Vec<u8>), Error> {
+        let scheme = [
+            EncryptionScheme::Aes128Cbc,
+            EncryptionScheme::Aes256Cbc,
+            EncryptionScheme::Aes128Gcm,
+            EncryptionScheme::Aes256Gcm,
+        ]
+        .iter()
+        .cloned()
+        .find(|scheme| *scheme.oid() == algorithm.algorithm)
+        .ok_or_else(|| {
+            format_err!("pbes2: unsupported encryption scheme: {:?}", algorithm.algorithm)
+        })?;
+
+        let iv = match (scheme, &algorithm.parameters) {
+            // [https://tools.ietf.org/html/rfc3565#section-4.1]
+            // AES-IV ::= OCTET STRING (SIZE(16))
+            (EncryptionScheme::Aes128Cbc, Some(ASN1Block::OctetString(_, iv)))
+            | (EncryptionScheme::Aes256Cbc, Some(ASN1Block::OctetString(_, iv))) => iv.clone(),
+
+            // [https://tools.ietf.org/html/rfc5084#section-3.2]
+            // GCMParameters ::= SEQUENCE {
+            //      aes-nonce        OCTET STRING,
+            //      aes-ICVlen       AES-GCM-ICVlen DEFAULT 12 }
+            (EncryptionScheme::Aes128Gcm, Some(ASN1Block::Sequence(_, blocks)))
+            | (EncryptionScheme::Aes256Gcm, Some(ASN1Block::Sequence(_, blocks))) => {
+                let tag_length = match blocks.get(1) {
+                    Some(ASN1Block::Integer(_, length)) => length.to_usize(),
+                    _ => Some(12),
+                };
+
+                if tag_length != Some(GCM_TAG_LENGTH) {
+                    bail!("pbes2: unsupported AES-GCM tag length: {:?}", tag_length);
+                }
+
+                match blocks.get(0) {
+                    Some(ASN1Block::OctetString(_, nonce)) => nonce.clone(),
+                    block => bail!("pbes2: expected `OCTET STRING`; found: {:?}", block),
+                }
+            }
+
+            (_, parameters) => bail!("pbes2: invalid {:?} parameters: {:?}", scheme, parameters),
+        };
+
+        if iv.len() != scheme.iv_length() {
+            bail!("pbes2: invalid {:?} IV length: {}", scheme, iv.len());
+        }
+
+        Ok((scheme, iv))
+    }
+
+    fn to_algorithm(self, iv: &[u8]) -> AlgorithmIdentifier {
+        let parameters = match self {
+            EncryptionScheme::Aes128Cbc | EncryptionScheme::Aes256Cbc => {
+                ASN1Block::OctetString(0, iv.to_vec())
+            }
+
+            EncryptionScheme::Aes128Gcm | EncryptionScheme::Aes256Gcm => ASN1Block::Sequence(
+                0,
+                vec![
+                    ASN1Block::OctetString(0, iv.to_vec()),
+                    ASN1Block::Integer(0, BigInt::from(GCM_TAG_LENGTH)),
+                ],
+            ),
+        };
+
+        AlgorithmIdentifier { algorithm: self.oid().clone(), parameters: Some(parameters) }
+    }
+
+    fn encrypt(self, key: &[u8], iv: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            EncryptionScheme::Aes128Cbc => Ok(Cbc::<Aes128, Pkcs7>::new_var(key, iv)
+                .map_err(|e| format_err!("pbes2: {:?}", e))?
+                .encrypt_vec(plaintext)),
+
+            EncryptionScheme::Aes256Cbc => Ok(Cbc::<Aes256, Pkcs7>::new_var(key, iv)
+                .map_err(|e| format_err!("pbes2: {:?}", e))?
+                .encrypt_vec(plaintext)),
+
+            EncryptionScheme::Aes128Gcm | EncryptionScheme::Aes256Gcm => {
+                let mut data = plaintext.to_vec();
+
+                self.gcm_key(key)?
+                    .seal_in_place_append_tag(gcm_nonce(iv)?, Aad::empty(), &mut data)
+                    .map_err(|_| format_err!("pbes2: failed to encrypt"))?;
+
+                Ok(data)
+            }
+        }
+    }
+
+    // A wrong passphrase is only detected by the padding with CBC, and by the tag with GCM
+    fn decrypt(
+        self,
+        key: &[u8],
+        iv: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let wrong_passphrase = || format_err!("pbes2: failed to decrypt; wrong passphrase?");
+
+        match self {
+            EncryptionScheme::Aes128Cbc => Cbc::<Aes128, Pkcs7>::new_var(key, iv)
+                .map_err(|e| format_err!("pbes2: {:?}", e))?
+                .decrypt_vec(ciphertext)
+                .map(Zeroizing::new)
+                .map_err(|_| wrong_passphrase()),
+
+            EncryptionScheme::Aes256Cbc => Cbc::<Aes256, Pkcs7>::new_var(key, iv)
+                .map_err(|e| format_err!("pbes2: {:?}", e))?
+                .decrypt_vec(ciphertext)
+                .map(Zeroizing::new)
+                .map_err(|_| wrong_passphrase()),
+
+            EncryptionScheme::Aes128Gcm | EncryptionScheme::Aes256Gcm => {
+                let mut data = Zeroizing::new(ciphertext.to_vec());
+
+                let length = self
+                    .gcm_key(key)?
+                    .open_in_place(gcm_nonce(iv)?, Aad::empty(), &mut data)
+                    .map_err(|_| wrong_passphrase())?
+                    .len();
+
+                data.truncate(length);
+                Ok(data)
+            }
+        }
+    }
+
+    fn gcm_key(self, key: &[u8]) -> Result<LessSafeKey, Error> {
+        let algorithm = match self {
+            EncryptionScheme::Aes128Gcm => &aead::AES_128_GCM,
+            _ => &aead::AES_256_GCM,
+        };
+
+        Ok(LessSafeKey::new(
+            UnboundKey::new(algorithm, key).map_err(|_| format_err!("pbes2: invalid key"))?,
+        ))
+    }
+}
+
+fn gcm_nonce(iv: &[u8]) -> Result<Nonce, Error> {
+    Nonce::try_assume_unique_for_key(iv).map_err(|_| format_err!("pbes2: invalid nonce"))
+}
+
+// [https://tools.ietf.org/html/rfc8018#appendix-A.2]
+// PBKDF2-params ::= SEQUENCE {
+//      salt              OCTET STRING,
+//      iterationCount    INTEGER (1..MAX),
+//      keyLength         INTEGER (1..MAX) OPTIONAL,
+//      prf               AlgorithmIdentifier DEFAULT algid-hmacWithSHA1 }
+
+struct Pbkdf2 {
+    salt: Vec<u8>,
+    iterations: NonZeroU32,
+    prf: pbkdf2::Algorithm,
+}
+
+fn prfs() -> [(&'static OID, pbkdf2::Algorithm); 4] {
+    [
+        (&OID_HMAC_WITH_SHA1, pbkdf2::PBKDF2_HMAC_SHA1),
+        (&OID_HMAC_WITH_SHA256, pbkdf2::PBKDF2_HMAC_SHA256),
+        (&OID_HMAC_WITH_SHA384, pbkdf2::PBKDF2_HMAC_SHA384),
+        (&OID_HMAC_WITH_SHA512, pbkdf2::PBKDF2_HMAC_SHA512),
+    ]
+}
+
+impl Pbkdf2 {
+    fn from_algorithm(algorithm: &AlgorithmIdentifier) -> Result<Self, Error> {
+        if algorithm.algorithm != *OID_PBKDF2 {
+            bail!("pbes2: unsupported key derivation function: {:?}", algorithm.algorithm);
+        }
+
+        let blocks = match &algorithm.parameters {
+            Some(ASN1Block::Sequence(_, blocks)) => blocks,
+            parameters => bail!("pbes2: invalid PBKDF2 parameters: {:?}", parameters),
+        };
+
+        let salt = match blocks.get(0) {
+            Some(ASN1Block::OctetString(_, salt)) => salt.clone(),
+            block => bail!("pbes2: expected `OCTET STRING`; found: {:?}", block),
+        };
+
+        let iterations = match blocks.get(1) {
+            Some(ASN1Block::Integer(_, iterations)) => {
+                iterations.to_u32().and_then(NonZeroU32::new)
+            }
+
+            _ => None,
+        }
+        .ok_or_else(|| format_err!("pbes2: invalid PBKDF2 iteration count: {:?}", blocks.get(1)))?;
+
+        if iterations.get() > PBKDF2_MAX_ITERATIONS {
+            bail!(
+                "pbes2: PBKDF2 iteration count of {} is above the maximum of {}",
+                iterations,
+                PBKDF2_MAX_ITERATIONS
+            );
+        }
+
+        // The key length is implied by the encryption scheme
+        let prf = match blocks.iter().skip(2).find(|block| !is_integer(block)) {
+            Some(block) => {
+                let (prf, _): (AlgorithmIdentifier, _) =
+                    FromASN1::from_asn1(std::slice::from_ref(block))?;
+
+                prfs()
+                    .iter()
+                    .find(|(oid, _)| **oid == prf.algorithm)
+                    .map(|(_, algorithm)| *algorithm)
+                    .ok_or_else(|| format_err!("pbes2: unsupported PRF: {:?}", prf.algorithm))?
+            }
+
+            None => pbkdf2::PBKDF2_HMAC_SHA1,
+        };
+
+        Ok(Self { salt, iterations, prf })
+    }
+
+    fn to_algorithm(&self) -> AlgorithmIdentifier {
+        // NOTE: Not possible to fail; every PRF has an OID
+        let (prf, _) =
+            prfs().iter().find(|(_, algorithm)| **algorithm == self.prf).cloned().unwrap();
+
+        let prf =
+            AlgorithmIdentifier { algorithm: prf.clone(), parameters: Some(ASN1Block::Null(0)) };
+
+        AlgorithmIdentifier {
+            algorithm: OID_PBKDF2.clone(),
+            parameters: Some(ASN1Block::Sequence(
+                0,
+                vec![
+                    ASN1Block::OctetString(0, self.salt.clone()),
+                    ASN1Block::Integer(0, BigInt::from(self.iterations.get())),
+                    prf.to_asn1_block(),
+                ],
+            )),
+        }
+    }
+
+    fn derive(&self, passphrase: &[u8], key_length: usize) -> Zeroizing<Vec<u8>> {
+        let mut key = Zeroizing::new(vec![0; key_length]);
+        pbkdf2::derive(self.prf, self.iterations, &self.salt, passphrase, &mut key);
+
+        key
+    }
+}
+
+fn is_integer(block: &ASN1Block) -> bool {
+    match block {
+        ASN1Block::Integer(..) => true,
+        _ => false,
+    }
+}
+
+// [https://tools.ietf.org/html/rfc5208#section-6]
+// EncryptedPrivateKeyInfo ::= SEQUENCE {
+//      encryptionAlgorithm  EncryptionAlgorithmIdentifier,
+//      encryptedData        EncryptedData }
+//
+// [https://tools.ietf.org/html/rfc8018#appendix-A.4]
+// PBES2-params ::= SEQUENCE {
+//      keyDerivationFunc AlgorithmIdentifier {{PBES2-KDFs}},
+//      encryptionScheme AlgorithmIdentifier {{PBES2-Encs}} }
+
+// Encrypt `der`, a `PrivateKeyInfo`, into an `EncryptedPrivateKeyInfo`
+pub(super) fn encrypt(
+    der: &[u8],
+    passphrase: &[u8],
+    scheme: EncryptionScheme,
+) -> Result<Vec<u8>, Error> {
+    let rng = SystemRandom::new();
+
+    let mut salt = vec![0; SALT_LENGTH];
+    let mut iv = vec![0; scheme.iv_length()];
+
+    rng.fill(&mut salt).map_err(|_| format_err!("pbes2: failed to generate a salt"))?;
+    rng.fill(&mut iv).map_err(|_| format_err!("pbes2: failed to generate an IV"))?;
+
+    let kdf = Pbkdf2 {
+        salt,
+        // NOTE: Not possible to fail; the constant is not zero
+        iterations: NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+        prf: pbkdf2::PBKDF2_HMAC_SHA256,
+    };
+
+    let key = kdf.derive(passphrase, scheme.key_length());
+    let encrypted = scheme.encrypt(&key, &iv, der)?;
+
+    let algorithm = AlgorithmIdentifier {
+        algorithm: OID_PBES2.clone(),
+        parameters: Some(ASN1Block::Sequence(
+            0,
+            vec![kdf.to_algorithm().to_asn1_block(), scheme.to_algorithm(&iv).to_asn1_block()],
+        )),
+    };
+
+    Ok(to_der(&ASN1Block::Sequence(
+        0,
+        vec![algorithm.to_asn1_block(), ASN1Block::OctetString(0, encrypted)],
+    ))
+    .map_err(ASN1Error::from)?)
+}
+
+// Decrypt `der`, an `EncryptedPrivateKeyInfo`, into a `PrivateKeyInfo`
+pub(super) fn decrypt(der: &[u8], passphrase: &[u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let (algorithm, encrypted) = match decode_der(der)?.into_iter().next() {
+        Some(ASN1Block::Sequence(_, blocks)) => {
+            let (algorithm, blocks): (AlgorithmIdentifier, _) = FromASN1::from_asn1(&blocks)?;
+
+            match blocks.get(0) {
+                Some(ASN1Block::OctetString(_, encrypted)) => (algorithm, encrypted.clone()),
+                block => bail!("pbes2: expected `OCTET STRING`; found: {:?}", block),
+            }
+        }
+
+        block => bail!("pbes2: expected `SEQUENCE`; found: {:?}", block),
+    };
+
+    if algorithm.algorithm != *OID_PBES2 {
+        bail!("pbes2: unsupported encryption algorithm: {:?}", algorithm.algorithm);
+    }
+
+    let (kdf, scheme) = match &algorithm.parameters {
+        Some(ASN1Block::Sequence(_, blocks)) => {
+            let (kdf, blocks): (AlgorithmIdentifier, _) = FromASN1::from_asn1(blocks)?;
+            let (scheme, _): (AlgorithmIdentifier, _) = FromASN1::from_asn1(blocks)?;
+
+            (kdf, scheme)
+        }
+
+        parameters => bail!("pbes2: invalid parameters: {:?}", parameters),
+    };
+
+    let (scheme, iv) = EncryptionScheme::from_algorithm(&scheme)?;
+    let key = Pbkdf2::from_algorithm(&kdf)?.derive(passphrase, scheme.key_length());
+
+    scheme.decrypt(&key, &iv, &encrypted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pbkdf2(iterations: u64) -> AlgorithmIdentifier {
+        AlgorithmIdentifier {
+            algorithm: OID_PBKDF2.clone(),
+            parameters: Some(ASN1Block::Sequence(
+                0,
+                vec![
+                    ASN1Block::OctetString(0, vec![0; SALT_LENGTH]),
+                    ASN1Block::Integer(0, BigInt::from(iterations)),
+                ],
+            )),
+        }
+    }
+
+    #[test]
+    fn test_iteration_bounds() {
+        let max = u64::from(PBKDF2_MAX_ITERATIONS);
+
+        assert!(Pbkdf2::from_algorithm(&pbkdf2(1)).is_ok());
+        assert!(Pbkdf2::from_algorithm(&pbkdf2(max)).is_ok());
+
+        assert!(Pbkdf2::from_algorithm(&pbkdf2(0)).is_err());
+        assert!(Pbkdf2::from_algorithm(&pbkdf2(max + 1)).is_err());
+        assert!(Pbkdf2::from_algorithm(&pbkdf2(0xffff_ffff)).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+// Textual encoding of keys; base64 DER between `-----BEGIN {label}-----` and `-----END`
+// [https://tools.ietf.org/html/rfc7468]
+use failure::{bail, Error};
+use zeroize::Zeroizing;
+
+pub(super) const LABEL_PRIVATE_KEY: &str = "PRIVATE KEY";
+pub(super) const LABEL_ENCRYPTED_PRIVATE_KEY: &str = "ENCRYPTED PRIVATE KEY";
+pub(super) const LABEL_EC_PRIVATE_KEY: &str = "EC PRIVATE KEY";
+pub(super) const LABEL_RSA_PRIVATE_KEY: &str = "RSA PRIVATE KEY";
+pub(super) const LABEL_PUBLIC_KEY: &str = "PUBLIC KEY";
+pub(super) const LABEL_RSA_PUBLIC_KEY: &str = "RSA PUBLIC KEY";
+
+// Lines of base64 are wrapped at 64 characters
+const LINE_LENGTH: usize = 64;
+
+// The PEM of a secret key is zeroed on drop, as is every copy of it made here
+pub(super) fn encode(label: &str, der: &[u8]) -> Zeroizing<String> {
+    let mut pem = Zeroizing::new(String::with_capacity(der.len() * 2));
+    pem.push_str(&format!("-----BEGIN {}-----\n", label));
+
+    for line in Zeroizing::new(base64::encode(der)).as_bytes().chunks(LINE_LENGTH) {
+        // NOTE: Not possible to fail; base64 is ASCII
+        pem.push_str(std::str::from_utf8(line).unwrap());
+        pem.push('\n');
+    }
+
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+// Decode the first PEM block of `pem` into its label and DER
+pub(super) fn decode(pem: &str) -> Result<(String, Zeroizing<Vec<u8>>), Error> {
+    let mut lines = pem.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    let label = match lines.next() {
+        Some(line) if line.starts_with("-----BEGIN ") && line.ends_with("-----") => {
+            &line["-----BEGIN ".len()..line.len() - "-----".len()]
+        }
+
+        line => bail!("pem: expected `-----BEGIN`; found: {:?}", line),
+    };
+
+    let end = format!("-----END {}-----", label);
+    let mut body = Zeroizing::new(String::new());
+
+    for line in lines {
+        if line == end {
+            return Ok((label.to_string(), Zeroizing::new(base64::decode(&*body)?)));
+        }
+
+        if line.contains(':') {
+            bail!("pem: headers are not supported; found: {:?}", line);
+        }
+
+        body.push_str(line);
+    }
+
+    bail!("pem: expected `{}`", end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pem() -> Result<(), Error> {
+        let der = vec![0x30; 100];
+        let pem = encode(LABEL_PUBLIC_KEY, &der);
+
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\n"));
+        assert!(pem.ends_with("-----END PUBLIC KEY-----\n"));
+        assert!(pem.lines().all(|line| line.len() <= LINE_LENGTH));
+
+        let (label, decoded) = decode(&pem)?;
+        assert_eq!(label, LABEL_PUBLIC_KEY);
+        assert_eq!(*decoded, der);
+        assert!(decode(&pem.replace("-----END PUBLIC KEY-----", "")).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,377 @@
+// RSA-3072 keys and signatures, with PKCS#1 v1.5 padding and SHA-384
+use super::{decode_der, AlgorithmIdentifier, PrivateKeyInfo, SubjectPublicKeyInfo};
+use failure::{bail, format_err, Error};
+use once_cell::sync::Lazy;
+use ring::{
+    rand::SystemRandom,
+    signature::{
+        KeyPair, RsaKeyPair, UnparsedPublicKey, RSA_PKCS1_3072_8192_SHA384, RSA_PKCS1_SHA384,
+    },
+};
+use simple_asn1::{der_decode, der_encode, oid, ASN1Block, OID};
+use std::fmt::{self, Debug, Display};
+use zeroize::{Zeroize, Zeroizing};
+
+// [https://tools.ietf.org/html/rfc8017#appendix-C]
+pub(super) static OID_RSA_ENCRYPTION: Lazy<OID> =
+    Lazy::new(|| { oid!(1, 2, 840, 113_549, 1, 1, 1) });
+
+/// Length of an RSA-3072 modulus, and so of its signatures.
+pub const RSA_SIGNATURE_LENGTH: usize = 384;
+
+const RSA_MODULUS_BITS: usize = RSA_SIGNATURE_LENGTH * 8;
+
+// [https://tools.ietf.org/html/rfc8017#appendix-A.1]
+// RSAPublicKey ::= SEQUENCE {
+//      modulus           INTEGER,  -- n
+//      publicExponent    INTEGER   -- e }
+//
+// RSAPrivateKey ::= SEQUENCE {
+//      version           Version,
+//      modulus           INTEGER,  -- n
+//      ...               -- the exponents, primes and coefficient }
+
+fn rsa_algorithm() -> AlgorithmIdentifier {
+    AlgorithmIdentifier {
+        algorithm: OID_RSA_ENCRYPTION.clone(),
+        parameters: Some(ASN1Block::Null(0)),
+    }
+}
+
+/// An RSA-3072 public key.
+#[derive(PartialEq, Clone)]
+pub struct RsaPublicKey(Vec<u8>);
+
+impl RsaPublicKey {
+    /// Construct an `RsaPublicKey` from a slice of bytes.
+    /// Bytes are expected to be either an `RSAPublicKey` (PKCS#1) or a `SubjectPublicKeyInfo`
+    /// in ASN.1.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let bytes = bytes.as_ref();
+
+        match der_decode::<SubjectPublicKeyInfo>(bytes) {
+            Ok(info) => Self::from_info(&info),
+            Err(_) => Self::from_rsa_public_key(bytes),
+        }
+    }
+
+    pub(super) fn from_info(info: &SubjectPublicKeyInfo) -> Result<Self, Error> {
+        if info.algorithm.algorithm != *OID_RSA_ENCRYPTION {
+            bail!(
+                "rsa: unknown public key algorithm: {:?}",
+                info.algorithm.algorithm
+            );
+        }
+
+        Self::from_rsa_public_key(&info.subject_public_key)
+    }
+
+    fn from_rsa_public_key(der: &[u8]) -> Result<Self, Error> {
+        let modulus = match decode_der(der)?.as_slice() {
+            [ASN1Block::Sequence(_, values)] => match values.as_slice() {
+                [ASN1Block::Integer(_, modulus), ASN1Block::Integer(..)] => modulus.bits(),
+                _ => bail!("rsa: expected `RSAPublicKey`"),
+            },
+
+            _ => bail!("rsa: expected `RSAPublicKey`"),
+        };
+
+        if modulus != RSA_MODULUS_BITS {
+            bail!("rsa: expected a {}-bit key; found {} bits", RSA_MODULUS_BITS, modulus);
+        }
+
+        Ok(RsaPublicKey(der.to_vec()))
+    }
+
+    /// Return the `RsaPublicKey` as an `RSAPublicKey` (PKCS#1) in ASN.1.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Format an `RsaPublicKey` as a vec of bytes in ASN.1 (`SubjectPublicKeyInfo`) format.
+    pub fn to_encoded_bytes(&self) -> Vec<u8> {
+        der_encode(&SubjectPublicKeyInfo {
+            algorithm: rsa_algorithm(),
+            subject_public_key: self.0.clone(),
+        })
+        // NOTE: Not possible to fail. Only fail case the library has is if OIDs are
+        //       given incorrectly.
+        .unwrap()
+    }
+
+    /// Verify a PKCS#1 v1.5 signature on a message with this `RsaPublicKey`.
+    ///
+    /// The message is hashed with SHA-384.
+    pub fn verify(
+        &self,
+        message: impl AsRef<[u8]>,
+        signature: &RsaSignature,
+    ) -> Result<bool, Error> {
+        let key = UnparsedPublicKey::new(&RSA_PKCS1_3072_8192_SHA384, &self.0);
+
+        // NOTE: ring does not tell an invalid key apart from a signature that does not match
+        Ok(key.verify(message.as_ref(), &signature.0).is_ok())
+    }
+}
+
+impl Debug for RsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\"", self)
+    }
+}
+
+/// Format an `RsaPublicKey` as a hex representation of its bytes in ASN.1 format.
+impl Display for RsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.to_encoded_bytes()))
+    }
+}
+
+/// An RSA-3072 secret key.
+///
+/// Keys can not be generated here; they are issued by the custodian and read with `from_bytes`.
+///
+/// Our copy of the encoded key is zeroed on drop, but the key pair `ring` signs with keeps its
+/// own copy for the lifetime of the key and does not zero it. The `Debug` output only shows the
+/// public key.
+pub struct RsaSecretKey {
+    der: Vec<u8>,
+    public: RsaPublicKey,
+    key_pair: RsaKeyPair,
+}
+
+impl RsaSecretKey {
+    /// Construct an `RsaSecretKey` from a slice of bytes.
+    /// Bytes are expected to be an `RSAPrivateKey` (PKCS#1) or `PrivateKeyInfo` (PKCS#8)
+    /// in ASN.1.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let bytes = bytes.as_ref();
+
+        match der_decode::<PrivateKeyInfo>(bytes) {
+            Ok(info) => Self::from_info(&info),
+            Err(_) => Self::from_rsa_private_key(bytes),
+        }
+    }
+
+    pub(super) fn from_info(info: &PrivateKeyInfo) -> Result<Self, Error> {
+        if info.algorithm.algorithm != *OID_RSA_ENCRYPTION {
+            bail!(
+                "rsa: PKCS#8 wrapping contained private key with unknown algorithm: {:?}",
+                info.algorithm.algorithm
+            );
+        }
+
+        Self::from_rsa_private_key(&info.private_key)
+    }
+
+    fn from_rsa_private_key(der: &[u8]) -> Result<Self, Error> {
+        let key_pair =
+            RsaKeyPair::from_der(der).map_err(|e| format_err!("rsa: invalid private key: {}", e))?;
+
+        let public = RsaPublicKey::from_bytes(key_pair.public_key().as_ref())?;
+
+        Ok(RsaSecretKey {
+            der: der.to_vec(),
+            public,
+            key_pair,
+        })
+    }
+
+    /// Return the `RsaSecretKey` as an `RSAPrivateKey` (PKCS#1) in ASN.1; this exposes the
+    /// secret.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// Format an `RsaSecretKey` as a vec of bytes in ASN.1 (PKCS#8) format; this exposes the
+    /// secret.
+    pub fn to_encoded_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(
+            der_encode(&PrivateKeyInfo {
+                algorithm: rsa_algorithm(),
+                private_key: self.der.clone(),
+            })
+            // NOTE: Not possible to fail. Only fail case the library has is if OIDs are
+            //       given incorrectly.
+            .unwrap(),
+        )
+    }
+
+    /// Derive an `RsaPublicKey` from this `RsaSecretKey`.
+    #[inline]
+    pub fn public(&self) -> RsaPublicKey {
+        self.public.clone()
+    }
+
+    /// Sign a message with this `RsaSecretKey`, with PKCS#1 v1.5 padding.
+    ///
+    /// The message is hashed with SHA-384.
+    pub fn sign(&self, message: impl AsRef<[u8]>) -> RsaSignature {
+        let mut signature = vec![0; self.key_pair.public_modulus_len()];
+
+        self.key_pair
+            .sign(&RSA_PKCS1_SHA384, &SystemRandom::new(), message.as_ref(), &mut signature)
+            // NOTE: Only fails if the signature buffer is too small or the system random number
+            //       generator fails (for blinding)
+            .expect("rsa: failed to sign");
+
+        RsaSignature(signature)
+    }
+}
+
+impl Clone for RsaSecretKey {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::from_rsa_private_key(&self.der).unwrap()
+    }
+}
+
+impl Drop for RsaSecretKey {
+    fn drop(&mut self) {
+        self.der.zeroize();
+    }
+}
+
+impl Debug for RsaSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RsaSecretKey").field("public", &self.public).finish()
+    }
+}
+
+/// An RSA-3072 PKCS#1 v1.5 signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RsaSignature(Vec<u8>);
+
+impl RsaSignature {
+    /// Construct an `RsaSignature` from a slice of bytes.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let bytes = bytes.as_ref();
+
+        if bytes.len() != RSA_SIGNATURE_LENGTH {
+            bail!("rsa: signature length mismatch");
+        }
+
+        Ok(RsaSignature(bytes.to_vec()))
+    }
+
+    /// Return the `RsaSignature` as raw bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Format an `RsaSignature` as a hex representation of its bytes.
+impl Display for RsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RsaPublicKey, RsaSecretKey, RsaSignature};
+    use failure::Error;
+
+    const KEY_SECRET_PKCS8_HEX: &str = "\
+        308206fd020100300d06092a864886f70d0101010500048206e7308206e30201000282018100b8dfcacf71d6\
+        3f0ad607eda259ffc36bad60078254b3719f60493c7dae48f09c8cdf61549124fa9d6fc0323704afe09554c3\
+        6458a2e252eca45cda33712e7b25f7e59db999fdfed81a0aa826d7f7d70fbfa7b25478e933f7db7d93c31aa6\
+        bf42ca0bce261ea01e6ed7601e043416fd70c170b3d7c1fbfab8a8069d522e9a6e714e5f5cae3a19c6beeaed\
+        cf62eacf9afc05ffd79b694c53e554227640de1d09c2245bf4e820d6da30f5dc4b3dd78d7c7664019bdeb479\
+        00474d20323c71104b9da34c599ed87660f996dc2ae3fe25df3efa2e9aca57b74ba4664e3b1f39ce8a2619e8\
+        ea4660c35306ba42fca31f56d8a5bc73c1f80d8024b90670061da5e36414dca1a5c9a5d3c75018a77e49c7bf\
+        187010f5e87089ac20ba74f1a0d8db4104469553391be151c3cc2bab0d29a5ccef31cd80a94d235298203e3c\
+        84c6228cd076997b7376aee5bf4f3602e64eebfedbe6c3a2e23ccac70725a05bc9b5bc8ff276efcfc02e3dd8\
+        7a8a23a533f8c323d373e72d4a62d838fbd38ee244c8f94a244f0203010001028201800457e048395b6d85ba\
+        a6305e05114382646e021f2765992b6e5eaa18d074643ab950877fd8c7c3f4d8eaecadc0381ae59c8fd94155\
+        2a4e05188e19e3902ad06281a2c25984404f06bd588cbf6f897762fbaa069582205b1932988e552216281e25\
+        e85b17d2d65e2b55220147db556d9a2d8be69dbb5a9e76f7739854959b0931de43d29b41402e7179217722d2\
+        c8bd27b8985c74b3e79827a61a07d4844e0301ada054c39dff38dc6244f7a639f482a7b6b7c1a007760ef1f3\
+        37936be4c2c63ea1381753ead26351611f5d57e5fc1a6bfca1be05a7abc3a073e4f0abc884014636ff5a894f\
+        bf9ac3feee2a4f184562ab88ae575268c04cc4ddc2c412b458c9b7af25e18125ecc77e1ad35b8c020b7cfcd9\
+        e2e3e67a6d5bfee763816e7fa117ffb70645edfdd652d318cfa4e0d1af35b1d3f9ff5dcbd4897ec814fd775b\
+        3d617ce55234908bccd5f9f7b75c9ce65090af6d369698f376911662f3036395e76d149d5caec75fa48ef86b\
+        87ea1b98e75f775699e248cd0862af4fc9a96361c27dc10281c100f3bb239a635f6d6cdc9d721de72670e87b\
+        bc01fb3205c8cb003efa1ed01f6bd8abcd95ac3f3e6a9ba9b1bcaa6bab8e5d1686f3466a6711a28a1a37251d\
+        a1f449108d3310d1dd6c62a0b16953f83ecfca243b3fe152ae5434ebc9d88edfee234b891bd053eb7e6b5a50\
+        7732bb42611e603d03e1a2666fce0f7c6fcb383dbc1923209a8bd715f5a2bc5dd96894f77647b0e104e7f13e\
+        e4d515cb49a70ce427c4179c3806d7971f63b9642b36cca29caf774ab59919e09103a56c9cdf638c0a952102\
+        81c100c22e308773fc6161c1b6db7afe006a9c0a8c17feb22d5524f6fb4351e8ce062823f77e6436511ccde1\
+        275a0858793285473a3bb291306e9e9290ce7300d484f1d703e1026a0150a35ec71391ba86273a178f1368dc\
+        e67369a63d6d64e8454048f80b5afdfb3464750541a65e00190fa98c4de3e9638b2a9d7dd555ca4c71eea5dc\
+        e693b2d2f53f697e8b1797e1c7516d451a0be2acf0ca89faa302cd6e6f8e14dffc5964371ca35f52f524e194\
+        b3322aef1111549be64fe8ff783ed07ad91b6f0281c100eb5ee8a5fa9b38382de0da48686626194d0ca5b928\
+        788f8d02e2ce769d340ad906289606ee585f698df9b7a1b832c75bbc1a7caa9eac844a3c25cee547f593cc4f\
+        709c44fcf660e9ea170a096e2d724adaac61273eca5d457202cde043fe5c156105fc2c5eba0a0000beebbe0c\
+        ff6eef2f5d2533c8cae5eaec97a55fddb65d6c1fdb3f7a69997a1b806228eddb005d8141fc93e4d480f60a82\
+        f3d09fd16159ac174fbfc3c90c2628edc411ba92bacf745b307df690785a56114a792c501c14210281c03f07\
+        b136f69e309a03c030441f18d1822f26444fa75471b3dafbba61671921047c90449590d0097286a83860471f\
+        a8177d045906607c811bb5e58d2de55216894dcaf389ce10f934de79ee4f3a657b6c2ee38160637be6709fc6\
+        86b0df784322ac693b33000c84a33b7e90db285afa2b85e06e9f7e7158f6e036946f631b0465106c43fb22e0\
+        faa16ab309888280295d37b5f9afad8547a93c1317c0c91d2ad879d036d001d7a8ec3348619d5ba043238e90\
+        52b8cd1618504044aed70b5957bf0281c075d5665446e30b39b1a61f48b96b53ee61dfa469f063a26db6a360\
+        95956714de01bbbaebd45451e0f38df416e826844a6989f003ab140eb40bc1de756cfcdf68c266d35d9d1e18\
+        0717a08b07524f319d41f5a4ef4ebbb5891555dff9c665655eddfaf82db74671e1b2f257751901281c621311\
+        1933720054e84242752d8b9879ecd3de146eecd4075446f0585a5bf87dcf9f67d25f394499c89562cd78cf1f\
+        52b1071e813d7af133bfddf504867b96cedab8806e984b3e300275851d627b99c3";
+
+    const KEY_PUBLIC_SPKI_HEX: &str = "\
+        308201a2300d06092a864886f70d01010105000382018f003082018a0282018100b8dfcacf71d63f0ad607ed\
+        a259ffc36bad60078254b3719f60493c7dae48f09c8cdf61549124fa9d6fc0323704afe09554c36458a2e252\
+        eca45cda33712e7b25f7e59db999fdfed81a0aa826d7f7d70fbfa7b25478e933f7db7d93c31aa6bf42ca0bce\
+        261ea01e6ed7601e043416fd70c170b3d7c1fbfab8a8069d522e9a6e714e5f5cae3a19c6beeaedcf62eacf9a\
+        fc05ffd79b694c53e554227640de1d09c2245bf4e820d6da30f5dc4b3dd78d7c7664019bdeb47900474d2032\
+        3c71104b9da34c599ed87660f996dc2ae3fe25df3efa2e9aca57b74ba4664e3b1f39ce8a2619e8ea4660c353\
+        06ba42fca31f56d8a5bc73c1f80d8024b90670061da5e36414dca1a5c9a5d3c75018a77e49c7bf187010f5e8\
+        7089ac20ba74f1a0d8db4104469553391be151c3cc2bab0d29a5ccef31cd80a94d235298203e3c84c6228cd0\
+        76997b7376aee5bf4f3602e64eebfedbe6c3a2e23ccac70725a05bc9b5bc8ff276efcfc02e3dd87a8a23a533\
+        f8c323d373e72d4a62d838fbd38ee244c8f94a244f0203010001";
+
+    const MESSAGE: &str = "This is a message about the world.";
+
+    const SIGNATURE: &str = "\
+        913bf0ad302724c815372a6354bc54ff9d4a22461b6970ae35671a117a8873cc4268991beb895e444bb83f8a\
+        4573e9b477e66ca4e513815aff2c37d6756194aecb156cea7276cc46afaaa6864594ef1f6c8b01f1348eb90c\
+        a7537cb3540bed9d9fa68be2b37525065e47f79a305c62660bde7adce3044ddf5637bbbd9cd6eb307bad205e\
+        6e28426af4ae4c26fd9731927bbde1d62f1bb6b9cb23f61fcbdebd982d907cb4eda1a450a1b5eea0c9e44a14\
+        5ba3a8d421669efab69a51e3b486d7f35ce81b869db1319777b81a4b01c6cfcee0ddfc2bc1606ad5273fad68\
+        64ade53713e57413e266cf524ff5c6291a216a0a668b0a2adf7bb39eca7686af05a9f68750ddce5ed3ae6e48\
+        bfc4468eb51184e1df05a303d202d705d1d6c831aa003a859334e8647e6726bae7db32d7b5a2a1323f0c8afc\
+        b87a741e61205c93e325fad3c0dbf6eb7d7813ebff98e4d67d23ff8ecf7c542ac46669d0d78d7dfb1ba02665\
+        841f6f68de26380ed5c3176c5c662877b9c35c9fc032b9ea772d9b4ede24c749";
+
+    #[test]
+    fn test_sign_verify() -> Result<(), Error> {
+        let secret = RsaSecretKey::from_bytes(hex::decode(KEY_SECRET_PKCS8_HEX)?)?;
+        let public = RsaPublicKey::from_bytes(hex::decode(KEY_PUBLIC_SPKI_HEX)?)?;
+
+        assert_eq!(secret.public(), public);
+
+        // PKCS#1 v1.5 signatures are deterministic
+        let signature = secret.sign(MESSAGE.as_bytes());
+        assert_eq!(signature.to_string(), SIGNATURE);
+
+        let signature = RsaSignature::from_bytes(hex::decode(SIGNATURE)?)?;
+        assert!(public.verify(MESSAGE.as_bytes(), &signature)?);
+        assert!(!public.verify(b"This is another message.", &signature)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoding() -> Result<(), Error> {
+        let secret = RsaSecretKey::from_bytes(hex::decode(KEY_SECRET_PKCS8_HEX)?)?;
+
+        assert_eq!(hex::encode(&*secret.to_encoded_bytes()), KEY_SECRET_PKCS8_HEX);
+        assert_eq!(RsaSecretKey::from_bytes(secret.as_bytes())?.public(), secret.public());
+
+        let public = secret.public();
+        assert_eq!(hex::encode(public.to_encoded_bytes()), KEY_PUBLIC_SPKI_HEX);
+        assert_eq!(RsaPublicKey::from_bytes(public.as_bytes())?, public);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+// Hierarchical deterministic derivation of ed25519 keys
+// [https://github.com/satoshilabs/slips/blob/master/slip-0010.md]
+use failure::{bail, format_err, Error};
+use ring::hmac;
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+// Indices at or above this are hardened; ed25519 only supports hardened derivation
+const HARDENED: u32 = 0x8000_0000;
+
+// [https://github.com/satoshilabs/slips/blob/master/slip-0044.md]
+const HEDERA_COIN_TYPE: u32 = 3030;
+
+/// A path of hardened child indices to derive an ed25519 key by, for example
+/// `m/44'/3030'/0'/0'/0'`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Construct a `DerivationPath` from child indices; each is hardened.
+    pub fn new(indices: &[u32]) -> Result<Self, Error> {
+        if let Some(index) = indices.iter().find(|&&index| index >= HARDENED) {
+            bail!("derivation path: index out of range: {}", index);
+        }
+
+        Ok(DerivationPath(indices.to_vec()))
+    }
+
+    /// The BIP-44 path of account key `index` for Hedera; `m/44'/3030'/0'/0'/{index}'`.
+    pub fn hedera(index: u32) -> Result<Self, Error> {
+        Self::new(&[44, HEDERA_COIN_TYPE, 0, 0, index])
+    }
+
+    /// The child indices of the path, without the hardened bit.
+    #[inline]
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut components = s.split('/');
+
+        if components.next() != Some("m") {
+            bail!("derivation path: expected `m/`: {:?}", s);
+        }
+
+        let indices = components
+            .map(|component| {
+                let index = if component.ends_with('\'') || component.ends_with('h') {
+                    &component[..component.len() - 1]
+                } else {
+                    bail!("derivation path: ed25519 only supports hardened indices: {:?}", s);
+                };
+
+                index
+                    .parse()
+                    .map_err(|_| format_err!("derivation path: invalid index: {:?}", component))
+            })
+            .collect::<Result<Vec<u32>, Error>>()?;
+
+        Self::new(&indices)
+    }
+}
+
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m")?;
+
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Derive the ed25519 secret key at `path` from `seed`; the chain code of the key is dropped
+pub(super) fn derive(seed: &[u8], path: &DerivationPath) -> [u8; 32] {
+    let (mut key, mut chain_code) = split(hmac::sign(
+        &hmac::Key::new(hmac::HMAC_SHA512, b"ed25519 seed"),
+        seed,
+    ));
+
+    for index in path.indices() {
+        let mut data = Zeroizing::new(Vec::with_capacity(1 + 32 + 4));
+        data.push(0);
+        data.extend_from_slice(&key);
+        data.extend_from_slice(&(index | HARDENED).to_be_bytes());
+
+        let (child_key, child_chain_code) =
+            split(hmac::sign(&hmac::Key::new(hmac::HMAC_SHA512, &chain_code), &data));
+
+        key.zeroize();
+        key = child_key;
+        chain_code = child_chain_code;
+    }
+
+    chain_code.zeroize();
+    key
+}
+
+fn split(tag: hmac::Tag) -> ([u8; 32], [u8; 32]) {
+    let mut key = [0; 32];
+    let mut chain_code = [0; 32];
+
+    key.copy_from_slice(&tag.as_ref()[..32]);
+    chain_code.copy_from_slice(&tag.as_ref()[32..]);
+
+    (key, chain_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // [https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-vector-1-for-ed25519]
+    const SEED_HEX: &str = "000102030405060708090a0b0c0d0e0f";
+
+    #[test]
+    fn test_derive() -> Result<(), Error> {
+        let seed = hex::decode(SEED_HEX)?;
+
+        assert_eq!(
+            hex::encode(derive(&seed, &"m".parse()?)),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+
+        assert_eq!(
+            hex::encode(derive(&seed, &"m/0'".parse()?)),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+
+        assert_eq!(
+            hex::encode(derive(&seed, &"m/0'/1'/2'/2'/1000000000'".parse()?)),
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_path() -> Result<(), Error> {
+        let path: DerivationPath = "m/44'/3030'/0h/0'/7'".parse()?;
+
+        assert_eq!(path, DerivationPath::hedera(7)?);
+        assert_eq!(path.to_string(), "m/44'/3030'/0'/0'/7'");
+
+        assert!("m/44'/3030'/0".parse::<DerivationPath>().is_err());
+        assert!("44'/3030'".parse::<DerivationPath>().is_err());
+        assert!("m/2147483648'".parse::<DerivationPath>().is_err());
+
+        Ok(())
+    }
+}
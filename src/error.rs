@@ -1,4 +1,4 @@
-use crate::Status;
+use crate::{abi::ContractRevert, Status};
 use failure_derive::Fail;
 
 #[derive(Debug, Fail)]
@@ -11,4 +11,124 @@ pub enum ErrorKind {
 
     #[fail(display = "pre-check failed with status: {:?}", _0)]
     PreCheck(Status),
+
+    #[fail(display = "contract call {}", _0)]
+    ContractRevert(ContractRevert),
+}
+
+/// A signature that is not valid, by its index in the batch it was verified in.
+#[derive(Debug, Fail, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    #[fail(display = "signature {} does not match its message and key", _0)]
+    BadSignature(usize),
+
+    #[fail(display = "signature {} was made with another algorithm than its key", _0)]
+    AlgorithmMismatch(usize),
+}
+
+/// A mnemonic phrase that is not valid.
+#[derive(Debug, Fail, Clone, PartialEq, Eq)]
+pub enum MnemonicError {
+    #[fail(display = "mnemonic: expected 12, 15, 18, 21 or 24 words; found {}", _0)]
+    WordCount(usize),
+
+    #[fail(display = "mnemonic: word {} is not in the word list: {:?}", index, word)]
+    UnknownWord { index: usize, word: String },
+
+    #[fail(display = "mnemonic: checksum mismatch; a word is wrong or out of place")]
+    Checksum,
+}
+
+#[derive(Debug, Fail)]
+pub enum AbiError {
+    #[fail(display = "expected at least {} bytes of ABI data; found {}", expected, found)]
+    BufferTooShort { expected: usize, found: usize },
+
+    #[fail(display = "ABI offset or length does not fit in memory")]
+    LengthOverflow,
+
+    #[fail(display = "ABI value does not fit in `{}`", _0)]
+    Overflow(&'static str),
+
+    #[fail(display = "{}", _0)]
+    Utf8(#[cause] std::string::FromUtf8Error),
+
+    #[fail(display = "invalid Solidity type: `{}`", _0)]
+    InvalidType(String),
+
+    #[fail(display = "invalid value for `{}`: {}", ty, reason)]
+    InvalidValue { ty: String, reason: String },
+
+    #[fail(display = "expected {} ABI values; found {}", expected, found)]
+    ArgumentCount { expected: usize, found: usize },
+
+    #[fail(
+        display = "fixed array length ({}) does not match the array length ({})",
+        expected, found
+    )]
+    ArrayLength { expected: usize, found: usize },
+
+    #[fail(
+        display = "Solidity integer width must be a multiple of 8 in the closed range [8, 256]; \
+                   found {}",
+        _0
+    )]
+    IntWidth(usize),
+
+    #[fail(display = "static ABI values must be a non-zero multiple of 32 bytes; found {}", _0)]
+    UnalignedValue(usize),
+
+    #[fail(display = "invalid Solidity address: {}", _0)]
+    InvalidAddress(String),
+
+    #[fail(display = "{} {} does not fit in a Solidity address", field, value)]
+    IdOutOfRange { field: &'static str, value: i128 },
+
+    #[fail(display = "invalid function selector: {}", _0)]
+    InvalidSelector(String),
+
+    #[fail(display = "no function `{}` in the contract ABI matches the given values", _0)]
+    UnknownFunction(String),
+
+    #[fail(display = "invalid function signature: `{}`", _0)]
+    InvalidSignature(String),
+
+    #[fail(display = "no function in the contract ABI has the selector {}", _0)]
+    UnknownSelector(String),
+
+    #[fail(display = "log topic does not match the signature of event `{}`", _0)]
+    TopicMismatch(String),
+
+    #[fail(display = "data does not start with the selector of `{}`", _0)]
+    SelectorMismatch(String),
+
+    #[fail(display = "no event in the contract ABI has the topic {}", _0)]
+    UnknownEvent(String),
+
+    #[fail(display = "invalid contract bytecode: {}", _0)]
+    InvalidBytecode(String),
+
+    #[fail(display = "no contract named `{}` in the compiler output", _0)]
+    UnknownContract(String),
+
+    #[fail(display = "the bytecode does not reference library `{}`", _0)]
+    UnknownLibrary(String),
+
+    #[fail(display = "library `{}` must be linked first", _0)]
+    UnlinkedLibrary(String),
+
+    #[fail(display = "{}", _0)]
+    Json(#[cause] serde_json::Error),
+}
+
+impl From<std::string::FromUtf8Error> for AbiError {
+    fn from(err: std::string::FromUtf8Error) -> Self {
+        AbiError::Utf8(err)
+    }
+}
+
+impl From<serde_json::Error> for AbiError {
+    fn from(err: serde_json::Error) -> Self {
+        AbiError::Json(err)
+    }
 }
@@ -1,9 +1,12 @@
 use crate::{
+    abi::{self, AbiValue, ContractAbi, ContractRevert, Event, ParamType},
+    error::AbiError,
     id::ContractId,
     proto::{self},
+    solidity_util::SolidityAddress,
 };
-use failure::Error;
 use hex;
+use num::{BigInt, BigUint, ToPrimitive};
 
 #[derive(Debug, Clone)]
 pub struct ContractLogInfo {
@@ -13,6 +16,19 @@ pub struct ContractLogInfo {
     pub data: Vec<u8>,
 }
 
+impl ContractLogInfo {
+    /// Might this log have been emitted for `event`? Checks the bloom filter of the log.
+    pub fn may_contain_event(&self, event: &Event) -> bool {
+        abi::bloom_contains(&self.bloom, &event.topic())
+    }
+
+    /// Decode the parameters of `event` from this log.
+    #[inline]
+    pub fn decode(&self, event: &Event) -> Result<Vec<AbiValue>, AbiError> {
+        event.decode_log(self)
+    }
+}
+
 impl From<proto::ContractCallLocal::ContractLoginfo> for ContractLogInfo {
     fn from(mut log: proto::ContractCallLocal::ContractLoginfo) -> Self {
         Self {
@@ -35,80 +51,217 @@ pub struct ContractFunctionResult {
 }
 
 impl ContractFunctionResult {
-    fn get_byte_buffer(&self, offset: usize) -> u8 {
-        self.contract_call_result[offset]
+    /// The reason the call reverted, if it did.
+    pub fn revert(&self) -> Option<ContractRevert> {
+        self.revert_data(ContractRevert::decode)
     }
 
-    fn get_int_value_at(&self, value_offset: usize) -> isize {
-        self.get_byte_buffer(value_offset + 28) as isize
+    /// The reason the call reverted, if it did, decoding custom errors declared in `abi`.
+    pub fn revert_with(&self, abi: &ContractAbi) -> Option<ContractRevert> {
+        self.revert_data(|data| ContractRevert::decode_with(data, abi))
     }
 
-    fn get_int_256(&self, val_index: usize) -> Vec<u8> {
-        self.contract_call_result[val_index * 32..(val_index + 1) * 32].to_vec()
+    // The network reports the revert data as hex in the error message, or in the
+    // call result itself
+    fn revert_data(&self, decode: impl FnOnce(&[u8]) -> ContractRevert) -> Option<ContractRevert> {
+        if self.error_message.is_empty() {
+            return None;
+        }
+
+        let message = self.error_message.trim_start_matches("0x");
+
+        Some(match hex::decode(message) {
+            Ok(ref data) if data.len() >= 4 => decode(data),
+            _ if self.contract_call_result.len() >= 4 => decode(&self.contract_call_result),
+            _ => ContractRevert::Message(self.error_message.clone()),
+        })
     }
 
-    fn get_array_length(&self, offset: usize) -> i64 {
-        let ln_start = offset + 24;
-        let ln_end = ln_start + 8;
-        let mut ln_bytes: [u8; 8] = Default::default();
-        ln_bytes.copy_from_slice(&self.contract_call_result[ln_start..ln_end]);
-        let ln = unsafe { std::mem::transmute::<[u8; 8], u64>(ln_bytes) }.to_be();
-        ln as i64
+    /// Might any log of this call have been emitted for `event`? Checks the bloom filter of
+    /// the result, which covers every log, before any log is decoded.
+    pub fn may_contain_event(&self, event: &Event) -> bool {
+        abi::bloom_contains(&self.bloom, &event.topic())
     }
 
-    pub fn get_int(&self, val_index: usize) -> isize {
-        self.get_int_value_at(val_index * 32)
+    /// Decode the parameters of every log of this call that was emitted for `event`.
+    pub fn events(&self, event: &Event) -> Result<Vec<Vec<AbiValue>>, AbiError> {
+        let topic = event.topic();
+
+        self.log_info
+            .iter()
+            .filter(|log| {
+                event.anonymous || log.topic.first().map(Vec::as_slice) == Some(&topic[..])
+            })
+            .map(|log| event.decode_log(log))
+            .collect()
+    }
+
+    /// Decode the whole result as a sequence of values of the given types.
+    pub fn decode(&self, types: &[ParamType]) -> Result<Vec<AbiValue>, AbiError> {
+        abi::decode(types, &self.contract_call_result)
     }
 
-    pub fn get_long(&self, val_index: usize) -> i64 {
-        self.get_int_value_at(val_index * 32 + 24) as i64
+    /// Decode the value of type `ty` at `val_index`, counting in 32-byte words from the start
+    /// of the result.
+    pub fn get_value(&self, val_index: usize, ty: &ParamType) -> Result<AbiValue, AbiError> {
+        abi::decode_at(ty, &self.contract_call_result, val_index * 32)
     }
 
-    pub fn get_bytes(&self, val_index: usize) -> Vec<u8> {
-        let offset = self.get_int(val_index) as usize;
-        let l = self.get_int_value_at(offset as usize) as usize;
-        self.contract_call_result[offset + 32..offset + 32 + l].to_vec()
+    /// Get a tuple (a Solidity `struct`) with members of the given types.
+    pub fn get_tuple(
+        &self,
+        val_index: usize,
+        members: &[ParamType],
+    ) -> Result<Vec<AbiValue>, AbiError> {
+        match self.get_value(val_index, &ParamType::Tuple(members.to_vec()))? {
+            AbiValue::Tuple(values) => Ok(values),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_signed(&self, val_index: usize, width: usize) -> Result<BigInt, AbiError> {
+        match self.get_value(val_index, &ParamType::Int(width))? {
+            AbiValue::Int(value) => Ok(value),
+            _ => unreachable!(),
+        }
     }
 
-    pub fn get_byte_array(&self, val_index: usize) -> Vec<Vec<u8>> {
-        let offset = (val_index * 32) + 32;
-        let start = offset + 32;
-        let ln = self.get_array_length(offset) as usize;
-        let mut b = Vec::new();
-        for i in 0..ln {
-            let b_offset = (i * 32) + start;
-            let chunk = self.contract_call_result[b_offset..b_offset + 32].to_vec();
-            b.push(chunk);
+    fn get_unsigned(&self, val_index: usize, width: usize) -> Result<BigUint, AbiError> {
+        match self.get_value(val_index, &ParamType::Uint(width))? {
+            AbiValue::Uint(value) => Ok(value),
+            _ => unreachable!(),
         }
-        b
     }
 
-    pub fn get_string(&self, val_index: usize) -> Result<String, Error> {
-        let b = self.get_bytes(val_index);
-        let s = String::from_utf8(b)?;
-        Ok(s)
+    pub fn get_int8(&self, val_index: usize) -> Result<i8, AbiError> {
+        self.get_signed(val_index, 8)?.to_i8().ok_or(AbiError::Overflow("i8"))
     }
 
-    pub fn get_bool(&self, val_index: usize) -> bool {
-        self.get_byte_buffer(val_index * 32 + 31) != 0
+    pub fn get_int16(&self, val_index: usize) -> Result<i16, AbiError> {
+        self.get_signed(val_index, 16)?.to_i16().ok_or(AbiError::Overflow("i16"))
     }
 
-    pub fn get_address(&self, val_index: usize) -> Vec<u8> {
-        let offset = val_index * 32;
-        self.contract_call_result[offset + 12..offset + 32].to_vec()
+    pub fn get_int32(&self, val_index: usize) -> Result<i32, AbiError> {
+        self.get_signed(val_index, 32)?.to_i32().ok_or(AbiError::Overflow("i32"))
     }
 
-    pub fn get_address_array(&self, val_index: usize) -> Vec<String> {
-        let offset = (val_index * 32) + 32;
-        let start = offset + 32;
-        let ln = self.get_array_length(offset) as usize;
-        let mut addrs = Vec::new();
-        for i in 0..ln {
-            let addr_offset = (i * 32) + start;
-            let chunk = self.contract_call_result[addr_offset + 12..addr_offset + 32].to_vec();
-            addrs.push(hex::encode(chunk))
+    pub fn get_int64(&self, val_index: usize) -> Result<i64, AbiError> {
+        self.get_signed(val_index, 64)?.to_i64().ok_or(AbiError::Overflow("i64"))
+    }
+
+    pub fn get_int128(&self, val_index: usize) -> Result<i128, AbiError> {
+        self.get_signed(val_index, 128)?.to_i128().ok_or(AbiError::Overflow("i128"))
+    }
+
+    /// Get a signed integer of any width up to 256 bits.
+    pub fn get_int256(&self, val_index: usize) -> Result<BigInt, AbiError> {
+        self.get_signed(val_index, 256)
+    }
+
+    pub fn get_uint8(&self, val_index: usize) -> Result<u8, AbiError> {
+        self.get_unsigned(val_index, 8)?.to_u8().ok_or(AbiError::Overflow("u8"))
+    }
+
+    pub fn get_uint16(&self, val_index: usize) -> Result<u16, AbiError> {
+        self.get_unsigned(val_index, 16)?.to_u16().ok_or(AbiError::Overflow("u16"))
+    }
+
+    pub fn get_uint32(&self, val_index: usize) -> Result<u32, AbiError> {
+        self.get_unsigned(val_index, 32)?.to_u32().ok_or(AbiError::Overflow("u32"))
+    }
+
+    pub fn get_uint64(&self, val_index: usize) -> Result<u64, AbiError> {
+        self.get_unsigned(val_index, 64)?.to_u64().ok_or(AbiError::Overflow("u64"))
+    }
+
+    pub fn get_uint128(&self, val_index: usize) -> Result<u128, AbiError> {
+        self.get_unsigned(val_index, 128)?.to_u128().ok_or(AbiError::Overflow("u128"))
+    }
+
+    /// Get an unsigned integer of any width up to 256 bits.
+    pub fn get_uint256(&self, val_index: usize) -> Result<BigUint, AbiError> {
+        self.get_unsigned(val_index, 256)
+    }
+
+    /// Get an `int32`.
+    #[inline]
+    pub fn get_int(&self, val_index: usize) -> Result<i32, AbiError> {
+        self.get_int32(val_index)
+    }
+
+    /// Get an `int64`.
+    #[inline]
+    pub fn get_long(&self, val_index: usize) -> Result<i64, AbiError> {
+        self.get_int64(val_index)
+    }
+
+    pub fn get_bytes(&self, val_index: usize) -> Result<Vec<u8>, AbiError> {
+        match self.get_value(val_index, &ParamType::Bytes)? {
+            AbiValue::Bytes(bytes) => Ok(bytes),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get a `bytesN`, where `len` is `N`.
+    pub fn get_fixed_bytes(&self, val_index: usize, len: usize) -> Result<Vec<u8>, AbiError> {
+        match self.get_value(val_index, &ParamType::FixedBytes(len))? {
+            AbiValue::FixedBytes(bytes) => Ok(bytes),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get a `bytes32[]`.
+    pub fn get_byte_array(&self, val_index: usize) -> Result<Vec<Vec<u8>>, AbiError> {
+        let ty = ParamType::Array(Box::new(ParamType::FixedBytes(32)));
+
+        match self.get_value(val_index, &ty)? {
+            AbiValue::Array(values) => Ok(values
+                .into_iter()
+                .map(|value| match value {
+                    AbiValue::FixedBytes(bytes) => bytes,
+                    _ => unreachable!(),
+                })
+                .collect()),
+
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_string(&self, val_index: usize) -> Result<String, AbiError> {
+        match self.get_value(val_index, &ParamType::String)? {
+            AbiValue::String(s) => Ok(s),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_bool(&self, val_index: usize) -> Result<bool, AbiError> {
+        match self.get_value(val_index, &ParamType::Bool)? {
+            AbiValue::Bool(b) => Ok(b),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_address(&self, val_index: usize) -> Result<SolidityAddress, AbiError> {
+        match self.get_value(val_index, &ParamType::Address)? {
+            AbiValue::Address(addr) => Ok(addr),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_address_array(&self, val_index: usize) -> Result<Vec<SolidityAddress>, AbiError> {
+        let ty = ParamType::Array(Box::new(ParamType::Address));
+
+        match self.get_value(val_index, &ty)? {
+            AbiValue::Array(values) => Ok(values
+                .into_iter()
+                .map(|value| match value {
+                    AbiValue::Address(addr) => addr,
+                    _ => unreachable!(),
+                })
+                .collect()),
+
+            _ => unreachable!(),
         }
-        addrs
     }
 }
 
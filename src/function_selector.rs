@@ -1,3 +1,5 @@
+use crate::abi::Function;
+use crate::error::AbiError;
 use sha3::{Digest, Keccak256};
 use std::string::ToString;
 
@@ -23,6 +25,42 @@ impl FunctionSelector {
         }
     }
 
+    /// Parse a human-readable signature such as `transfer(address,uint256)`.
+    ///
+    /// The parameter types are validated and normalized to their canonical form before the
+    /// selector is computed; for example `uint` becomes `uint256`.
+    pub fn parse(signature: &str) -> Result<Self, AbiError> {
+        let function = Function::parse(signature)?;
+
+        Ok(Self {
+            needs_comma: !function.inputs.is_empty(),
+            finished: function.signature().into_bytes(),
+            complete: true
+        })
+    }
+
+    /// The canonical signature of the function; e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        let mut f = String::from_utf8_lossy(&self.finished).into_owned();
+        if self.complete != true {
+            f.push(')');
+        }
+        f
+    }
+
+    /// The first 4 bytes of the calldata of a call of the function.
+    pub fn selector(&self) -> [u8; 4] {
+        let mut selector = [0; 4];
+        selector.copy_from_slice(&self.finish_intermediate()[..4]);
+        selector
+    }
+
+    /// A typed encoder for the parameters of the function; its `call_params` and
+    /// `encode_input` check every value against the parameter types of the signature.
+    pub fn encoder(&self) -> Result<Function, AbiError> {
+        Function::parse(&self.signature())
+    }
+
     pub(crate) fn add_param_type(&mut self, param_type: String) {
         if self.needs_comma == true {
             self.finished.extend(",".as_bytes().to_vec())
@@ -58,6 +96,7 @@ impl FunctionSelector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::abi::AbiValue;
 
     #[test]
     fn test_function_selector() {
@@ -94,4 +133,32 @@ mod tests {
         fs.add_param_type(t4.5);
         assert_eq!(hex::encode(fs.finish()[0..4].to_vec()), t4.0);
     }
+
+    #[test]
+    fn test_parse_function_selector() -> Result<(), AbiError> {
+        let fs = FunctionSelector::parse("transfer(address,uint)")?;
+
+        assert_eq!(fs.signature(), "transfer(address,uint256)");
+        assert_eq!(hex::encode(fs.selector()), "a9059cbb");
+
+        let mut built = FunctionSelector::new("transfer".to_string());
+        built.add_param_type("address".to_string());
+        built.add_param_type("uint256".to_string());
+        assert_eq!(built.selector(), fs.selector());
+
+        let encoder = fs.encoder()?;
+        let data = encoder.encode_input(&[
+            AbiValue::Address([0x11; 20].into()),
+            AbiValue::Uint(1000u32.into()),
+        ])?;
+        assert_eq!(data[..4], fs.selector());
+        assert_eq!(data.len(), 4 + 2 * 32);
+
+        match FunctionSelector::parse("transfer(address,uint7)") {
+            Err(AbiError::InvalidType(_)) => {}
+            result => panic!("unexpected result: {:?}", result.map(|fs| fs.signature())),
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,264 @@
+use crate::{
+    function_result::ContractFunctionResult, query::QueryContractCall, Client, ContractId,
+    ErrorKind, Status,
+};
+use failure::Error;
+use futures::Future;
+use std::convert::TryFrom;
+
+/// Gas added on top of an estimate, in percent of the estimate.
+pub const DEFAULT_GAS_MARGIN: u64 = 20;
+
+/// The default maximum gas of a single transaction on the network.
+pub const DEFAULT_MAX_GAS: i64 = 300_000;
+
+// The search stops once the range of gas limits is within 1/64th of its upper bound
+const SEARCH_TOLERANCE: i64 = 64;
+
+// Gas schedule of the Ethereum yellow paper, which the network's EVM follows
+const TX_GAS: u64 = 21_000;
+const CREATE_GAS: u64 = 32_000;
+const CODE_DEPOSIT_GAS: u64 = 200;
+const DATA_ZERO_GAS: u64 = 4;
+const DATA_NONZERO_GAS: u64 = 68;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasEstimate {
+    /// Gas used by the call when given the maximum gas; for contract creation, the static
+    /// cost of creating the contract.
+    pub gas_used: u64,
+
+    /// The gas limit to use; the estimate plus the safety margin, at most the maximum gas.
+    pub gas: i64,
+}
+
+impl GasEstimate {
+    fn new(gas_used: u64, estimate: i64, margin: u64, max_gas: i64) -> Self {
+        let margin = i64::try_from(margin).unwrap_or(i64::max_value());
+        let gas = estimate.saturating_add(estimate.saturating_mul(margin) / 100);
+
+        Self {
+            gas_used,
+            gas: gas.min(max_gas.max(estimate)),
+        }
+    }
+}
+
+/// Estimate the gas needed for a contract call.
+///
+/// The call is made with `QueryContractCall`s (which are paid for) over a binary search of
+/// the gas limit: first with the maximum gas, to check that the call succeeds at all, and then
+/// narrowing down on the smallest limit it succeeds with. The estimate is that limit plus
+/// a safety margin.
+///
+/// Local calls can not transfer any tinybars, so calls to payable functions that depend on
+/// the amount sent can not be estimated.
+pub struct GasEstimator<'a> {
+    client: &'a Client,
+    contract: ContractId,
+    function_parameters: Vec<u8>,
+    max_gas: i64,
+    margin: u64,
+}
+
+impl<'a> GasEstimator<'a> {
+    pub(crate) fn new(
+        client: &'a Client,
+        contract: ContractId,
+        function_parameters: Vec<u8>,
+    ) -> Self {
+        Self {
+            client,
+            contract,
+            function_parameters,
+            max_gas: DEFAULT_MAX_GAS,
+            margin: DEFAULT_GAS_MARGIN,
+        }
+    }
+
+    /// The largest gas limit to try; the estimate fails if the call does not succeed with it.
+    #[inline]
+    pub fn max_gas(&mut self, gas: i64) -> &mut Self {
+        self.max_gas = gas;
+        self
+    }
+
+    /// Gas to add on top of the estimate, in percent of the estimate.
+    #[inline]
+    pub fn margin(&mut self, percent: u64) -> &mut Self {
+        self.margin = percent;
+        self
+    }
+
+    pub async fn get_async(&mut self) -> Result<GasEstimate, Error> {
+        // If the call fails with the maximum gas, no gas limit will do; report why
+        let gas_used = self.call(self.max_gas).await?.gas_used;
+        let mut search = GasSearch::new(gas_used, self.max_gas);
+
+        while let Some(gas) = search.next() {
+            match self.call(gas).await {
+                Ok(_) => search.record(gas, true),
+                Err(error) if is_out_of_gas(&error) => search.record(gas, false),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(GasEstimate::new(gas_used, search.high, self.margin, self.max_gas))
+    }
+
+    pub fn get(&mut self) -> Result<GasEstimate, Error> {
+        crate::RUNTIME
+            .lock()
+            .block_on(self.get_async())
+    }
+
+    fn call(&self, gas: i64) -> impl Future<Output = Result<ContractFunctionResult, Error>> {
+        let mut query = QueryContractCall::new(
+            self.client,
+            self.contract,
+            gas,
+            self.function_parameters.clone(),
+            QueryContractCall::DEFAULT_MAX_RESULT_SIZE,
+        );
+
+        async move { query.get_async().await }
+    }
+}
+
+/// Estimate the gas needed to create a contract from its bytecode.
+///
+/// The network can not run a constructor without creating the contract (`contractCallLocal`
+/// only calls existing contracts), so this is a static estimate from the size of the bytecode:
+/// the base cost of a contract creation, the cost of the bytecode and constructor parameters
+/// as transaction data, and the cost of storing the runtime code (bounded by the bytecode,
+/// which contains it). The execution of the constructor itself is only covered by the margin;
+/// give constructors that do more than store their arguments a larger margin, or set the gas.
+///
+/// The estimate is capped at `DEFAULT_MAX_GAS` unless the static cost alone is higher.
+pub fn estimate_create_gas(
+    bytecode: &[u8],
+    constructor_parameters: &[u8],
+    margin: u64,
+) -> GasEstimate {
+    let data_gas = bytecode
+        .iter()
+        .chain(constructor_parameters)
+        .map(|b| if *b == 0 { DATA_ZERO_GAS } else { DATA_NONZERO_GAS })
+        .fold(0u64, u64::saturating_add);
+
+    let deposit_gas = CODE_DEPOSIT_GAS.saturating_mul(bytecode.len() as u64);
+    let gas = (TX_GAS + CREATE_GAS)
+        .saturating_add(data_gas)
+        .saturating_add(deposit_gas);
+
+    let estimate = i64::try_from(gas).unwrap_or(i64::max_value());
+
+    GasEstimate::new(gas, estimate, margin, DEFAULT_MAX_GAS)
+}
+
+// A gas limit is too low only if the call runs out of gas; any other failure, such as a
+// revert, would fail the same with more gas
+fn is_out_of_gas(error: &Error) -> bool {
+    match error.downcast_ref::<ErrorKind>() {
+        Some(ErrorKind::PreCheck(status)) => match status {
+            Status::InsufficientGas | Status::InsufficientLocalCallGas => true,
+            _ => false,
+        },
+
+        _ => false,
+    }
+}
+
+// Bisection over the gas limit; the call succeeds with `high` and fails with `low`
+struct GasSearch {
+    low: i64,
+    high: i64,
+}
+
+impl GasSearch {
+    fn new(gas_used: u64, max_gas: i64) -> Self {
+        // Any limit below the gas used by the call fails
+        Self {
+            low: (gas_used as i64 - 1).max(0).min(max_gas),
+            high: max_gas,
+        }
+    }
+
+    // The next gas limit to try, if the range has not narrowed down far enough yet
+    fn next(&self) -> Option<i64> {
+        if self.high - self.low <= (self.high / SEARCH_TOLERANCE).max(1) {
+            return None;
+        }
+
+        Some(self.low + (self.high - self.low) / 2)
+    }
+
+    fn record(&mut self, gas: i64, succeeded: bool) {
+        if succeeded {
+            self.high = gas;
+        } else {
+            self.low = gas;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::ContractRevert;
+
+    #[test]
+    fn test_gas_search() {
+        // A call that uses 30,000 gas but needs 45,000 to run, as with a refund
+        let needed = 45_000;
+        let mut search = GasSearch::new(30_000, DEFAULT_MAX_GAS);
+        let mut calls = 0;
+
+        while let Some(gas) = search.next() {
+            search.record(gas, gas >= needed);
+            calls += 1;
+        }
+
+        assert!(search.high >= needed);
+        assert!(search.high - needed <= search.high / SEARCH_TOLERANCE);
+        assert!(calls <= 16, "{} calls", calls);
+
+        let estimate = GasEstimate::new(30_000, search.high, DEFAULT_GAS_MARGIN, DEFAULT_MAX_GAS);
+        assert_eq!(estimate.gas, search.high * 120 / 100);
+    }
+
+    #[test]
+    fn test_estimate_capped_at_max_gas() {
+        let estimate = GasEstimate::new(290_000, 295_000, DEFAULT_GAS_MARGIN, DEFAULT_MAX_GAS);
+        assert_eq!(estimate.gas, DEFAULT_MAX_GAS);
+    }
+
+    #[test]
+    fn test_estimate_margin_overflow() {
+        let estimate = GasEstimate::new(290_000, 295_000, u64::max_value(), DEFAULT_MAX_GAS);
+        assert_eq!(estimate.gas, DEFAULT_MAX_GAS);
+
+        let estimate = GasEstimate::new(0, i64::max_value(), 50, i64::max_value());
+        assert_eq!(estimate.gas, i64::max_value());
+    }
+
+    #[test]
+    fn test_estimate_create_gas() {
+        let estimate = estimate_create_gas(&[0x60, 0x80, 0x00], &[0x00, 0x01], 0);
+
+        assert_eq!(estimate.gas_used, 21_000 + 32_000 + 68 * 3 + 4 * 2 + 200 * 3);
+        assert_eq!(estimate.gas, estimate.gas_used as i64);
+
+        // The margin never takes the estimate over the maximum gas
+        let estimate = estimate_create_gas(&[0x60; 700], &[], 50);
+        assert_eq!(estimate.gas_used, 53_000 + 268 * 700);
+        assert_eq!(estimate.gas, DEFAULT_MAX_GAS);
+    }
+
+    #[test]
+    fn test_is_out_of_gas() {
+        assert!(is_out_of_gas(&ErrorKind::PreCheck(Status::InsufficientGas).into()));
+        assert!(!is_out_of_gas(&ErrorKind::PreCheck(Status::ContractRevertExecuted).into()));
+        assert!(!is_out_of_gas(&ErrorKind::ContractRevert(ContractRevert::decode(&[])).into()));
+    }
+}
@@ -0,0 +1,345 @@
+// Password-encrypted files of named keys, each tagged with the account it is the key of
+use crate::{AccountId, PublicKey, SecretKey};
+use failure::{bail, format_err, Error};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+use zeroize::Zeroizing;
+
+/// The version of the keystore file format written by this SDK.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// A named key in a `Keystore`, with the account it is the key of.
+#[derive(Debug, Clone)]
+pub struct KeystoreKey {
+    pub name: String,
+    pub account: AccountId,
+    pub secret: SecretKey,
+}
+
+/// A set of named keys, stored as a versioned JSON file.
+///
+/// Each key is encrypted with AES-256-GCM, under a key derived from the password by scrypt.
+/// The name and account of a key are authenticated with it, but not encrypted.
+///
+/// ```ignore
+/// let keystore = Keystore::open("keystore.json", &env::var("KEYSTORE_PASSWORD")?)?;
+///
+/// let client = Client::builder("testnet.hedera.com:50131")
+///     .node("0:0:3".parse()?)
+///     .operator_from_keystore(&keystore, "operator")?
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    keys: Vec<KeystoreKey>,
+    scrypt: Scrypt,
+}
+
+impl Default for Keystore {
+    fn default() -> Self {
+        Self { keys: Vec::new(), scrypt: Scrypt::default() }
+    }
+}
+
+impl Keystore {
+    /// Construct an empty `Keystore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read and decrypt the keystore file at `path`.
+    pub fn open(path: impl AsRef<Path>, password: &str) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|e| format_err!("keystore: failed to read {:?}: {}", path, e))?;
+
+        Self::from_json(&json, password)
+    }
+
+    /// Encrypt the keystore with `password`, and write it to `path`.
+    pub fn save(&self, path: impl AsRef<Path>, password: &str) -> Result<(), Error> {
+        let path = path.as_ref();
+
+        fs::write(path, self.to_json(password)?)
+            .map_err(|e| format_err!("keystore: failed to write {:?}: {}", path, e))
+    }
+
+    /// Decrypt a keystore from its JSON.
+    pub fn from_json(json: &str, password: &str) -> Result<Self, Error> {
+        let raw: RawKeystore = serde_json::from_str(json)?;
+
+        if raw.version != KEYSTORE_VERSION {
+            bail!("keystore: unsupported version: {}", raw.version);
+        }
+
+        let mut keystore = Self::new();
+
+        for key in raw.keys {
+            let RawKdf::Scrypt { salt, log_n, r, p } = key.kdf;
+            let scrypt = Scrypt { log_n, r, p };
+
+            if key.cipher.algorithm != CIPHER_AES_256_GCM {
+                bail!("keystore: unsupported cipher: {:?}", key.cipher.algorithm);
+            }
+
+            let account = key.account.parse()?;
+            let aad = associated_data(&key.name, account);
+
+            let aead_key = cipher_key(&scrypt.derive(password, &hex::decode(&salt)?)?)?;
+            let aead_nonce = nonce(&hex::decode(&key.cipher.nonce)?)?;
+
+            let mut data = Zeroizing::new(hex::decode(&key.ciphertext)?);
+            let length = aead_key
+                .open_in_place(aead_nonce, Aad::from(&aad), &mut data)
+                .map_err(|_| {
+                    format_err!("keystore: failed to decrypt {:?}; wrong password?", key.name)
+                })?
+                .len();
+
+            data.truncate(length);
+            let secret = SecretKey::from_bytes(&*data)?;
+
+            if secret.public() != key.public_key.parse::<PublicKey>()? {
+                bail!("keystore: public key of {:?} does not match its secret key", key.name);
+            }
+
+            keystore.insert(&key.name, account, secret)?;
+
+            // Keep the cost the file was written with when it is saved again
+            keystore.scrypt = scrypt;
+        }
+
+        Ok(keystore)
+    }
+
+    /// Encrypt the keystore with `password` into its JSON.
+    pub fn to_json(&self, password: &str) -> Result<String, Error> {
+        let rng = SystemRandom::new();
+
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| {
+                let mut salt = [0; SALT_LENGTH];
+                let mut nonce_bytes = [0; NONCE_LENGTH];
+
+                rng.fill(&mut salt)
+                    .map_err(|_| format_err!("keystore: failed to generate a salt"))?;
+                rng.fill(&mut nonce_bytes)
+                    .map_err(|_| format_err!("keystore: failed to generate a nonce"))?;
+
+                let aad = associated_data(&key.name, key.account);
+                let mut data = key.secret.to_encoded_bytes();
+
+                cipher_key(&self.scrypt.derive(password, &salt)?)?
+                    .seal_in_place_append_tag(nonce(&nonce_bytes)?, Aad::from(&aad), &mut *data)
+                    .map_err(|_| format_err!("keystore: failed to encrypt {:?}", key.name))?;
+
+                Ok(RawKey {
+                    name: key.name.clone(),
+                    account: key.account.to_string(),
+                    public_key: key.secret.public().to_string(),
+                    kdf: RawKdf::Scrypt {
+                        salt: hex::encode(salt),
+                        log_n: self.scrypt.log_n,
+                        r: self.scrypt.r,
+                        p: self.scrypt.p,
+                    },
+                    cipher: RawCipher {
+                        algorithm: CIPHER_AES_256_GCM.to_string(),
+                        nonce: hex::encode(nonce_bytes),
+                    },
+                    ciphertext: hex::encode(&*data),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(serde_json::to_string_pretty(&RawKeystore { version: KEYSTORE_VERSION, keys })?)
+    }
+
+    /// Add the key `name` of `account`; fails if the keystore already has a key `name`.
+    pub fn insert(
+        &mut self,
+        name: &str,
+        account: AccountId,
+        secret: SecretKey,
+    ) -> Result<&mut Self, Error> {
+        if self.get(name).is_some() {
+            bail!("keystore: duplicate key name: {:?}", name);
+        }
+
+        self.keys.push(KeystoreKey { name: name.to_string(), account, secret });
+
+        Ok(self)
+    }
+
+    /// Remove the key `name`, if any.
+    pub fn remove(&mut self, name: &str) -> Option<KeystoreKey> {
+        let i = self.keys.iter().position(|key| key.name == name)?;
+
+        Some(self.keys.remove(i))
+    }
+
+    /// The key `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&KeystoreKey> {
+        self.keys.iter().find(|key| key.name == name)
+    }
+
+    /// The keys, in the order they were added.
+    pub fn keys(&self) -> &[KeystoreKey] {
+        &self.keys
+    }
+}
+
+// The name and account of a key are authenticated with it, so neither can be swapped
+fn associated_data(name: &str, account: AccountId) -> Vec<u8> {
+    format!("{}\0{}", name, account).into_bytes()
+}
+
+fn cipher_key(key: &[u8]) -> Result<LessSafeKey, Error> {
+    Ok(LessSafeKey::new(
+        UnboundKey::new(&AES_256_GCM, key).map_err(|_| format_err!("keystore: invalid key"))?,
+    ))
+}
+
+fn nonce(nonce: &[u8]) -> Result<Nonce, Error> {
+    Nonce::try_assume_unique_for_key(nonce).map_err(|_| format_err!("keystore: invalid nonce"))
+}
+
+// [https://tools.ietf.org/html/rfc7914]
+#[derive(Debug, Clone, Copy)]
+struct Scrypt {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+// The parameters are read from the file, so they are bounded before any memory is allocated
+const SCRYPT_MAX_LOG_N: u8 = 20;
+const SCRYPT_MAX_R: u32 = 16;
+const SCRYPT_MAX_P: u32 = 16;
+const SCRYPT_MAX_MEMORY: u64 = 1 << 30;
+
+// N = 2^15 takes 32 MiB and about a tenth of a second
+impl Default for Scrypt {
+    fn default() -> Self {
+        Self { log_n: 15, r: 8, p: 1 }
+    }
+}
+
+impl Scrypt {
+    fn derive(self, password: &str, salt: &[u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+        // scrypt needs 128 * r * N bytes
+        if self.log_n > SCRYPT_MAX_LOG_N
+            || self.r > SCRYPT_MAX_R
+            || self.p > SCRYPT_MAX_P
+            || (128 * u64::from(self.r)) << self.log_n > SCRYPT_MAX_MEMORY
+        {
+            bail!(
+                "keystore: scrypt parameters are too large: log_n = {}, r = {}, p = {}",
+                self.log_n,
+                self.r,
+                self.p
+            );
+        }
+
+        let params = scrypt::ScryptParams::new(self.log_n, self.r, self.p)
+            .map_err(|e| format_err!("keystore: invalid scrypt parameters: {}", e))?;
+
+        let mut key = Zeroizing::new(vec![0; KEY_LENGTH]);
+        scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+            .map_err(|e| format_err!("keystore: {}", e))?;
+
+        Ok(key)
+    }
+}
+
+const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+
+#[derive(Serialize, Deserialize)]
+struct RawKeystore {
+    version: u32,
+    keys: Vec<RawKey>,
+}
+
+// Binary fields are hex-encoded
+#[derive(Serialize, Deserialize)]
+struct RawKey {
+    name: String,
+    account: String,
+    public_key: String,
+    kdf: RawKdf,
+    cipher: RawCipher,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "function", rename_all = "lowercase")]
+enum RawKdf {
+    Scrypt { salt: String, log_n: u8, r: u32, p: u32 },
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawCipher {
+    algorithm: String,
+    nonce: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() -> Result<(), Error> {
+        let secret: SecretKey = "302e020100300506032b657004220420db484b828e64b2d8f12ce3c0a0e93a0b\
+                                 8cce7af1bb8f39c97732394482538e10"
+            .parse()?;
+
+        let account = AccountId::new(0, 0, 1001);
+
+        let mut keystore = Keystore::new();
+        keystore.scrypt.log_n = 10;
+        keystore.insert("operator", account, secret.clone())?;
+
+        assert!(keystore.insert("operator", account, secret.clone()).is_err());
+
+        let json = keystore.to_json("correct-horse")?;
+        assert!(!json.contains(&hex::encode(secret.as_bytes())));
+
+        let opened = Keystore::from_json(&json, "correct-horse")?;
+        let key = opened.get("operator").unwrap();
+
+        assert_eq!(key.account, account);
+        assert_eq!(key.secret.as_bytes(), secret.as_bytes());
+
+        assert!(Keystore::from_json(&json, "battery-staple").is_err());
+
+        // The account is authenticated with the key
+        let moved = json.replace("\"0:0:1001\"", "\"0:0:1002\"");
+        assert!(Keystore::from_json(&moved, "correct-horse").is_err());
+
+        let unversioned = json.replace("\"version\": 1", "\"version\": 2");
+        assert!(Keystore::from_json(&unversioned, "correct-horse").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scrypt_bounds() {
+        let salt = [0; SALT_LENGTH];
+
+        assert!(Scrypt { log_n: 10, r: 8, p: 1 }.derive("", &salt).is_ok());
+        assert!(Scrypt { log_n: 63, r: 8, p: 1 }.derive("", &salt).is_err());
+        assert!(Scrypt { log_n: 10, r: u32::max_value(), p: 1 }.derive("", &salt).is_err());
+        assert!(Scrypt { log_n: 10, r: 8, p: u32::max_value() }.derive("", &salt).is_err());
+        assert!(Scrypt { log_n: 20, r: 16, p: 1 }.derive("", &salt).is_err());
+    }
+}
@@ -4,19 +4,26 @@
 #[macro_use]
 mod macros;
 
+pub mod abi;
 mod argument;
+pub mod artifact;
+pub mod bytecode;
 pub mod call_params;
 mod call_param_utils;
 mod claim;
 pub mod client;
+mod contract_deploy;
 mod crypto;
 mod duration;
 mod entity;
 mod error;
+pub mod gas;
 mod id;
 mod info;
+pub mod keystore;
 mod proto;
 pub mod query;
+pub mod signer;
 pub mod status;
 pub mod solidity_util;
 mod timestamp;
@@ -30,11 +37,19 @@ pub mod function_selector;
 pub use self::{
     claim::Claim,
     client::Client,
-    crypto::{PublicKey, SecretKey, Signature},
+    contract_deploy::ContractDeploy,
+    crypto::{
+        DerivationPath, EcdsaPublicKey, EcdsaSecretKey, EcdsaSignature, EncryptionScheme,
+        LegacyWordList, Mnemonic, MnemonicLanguage, MnemonicScheme, PublicKey, RsaPublicKey,
+        RsaSecretKey, RsaSignature, SecretKey, Signature,
+    },
     entity::Entity,
-    error::ErrorKind,
+    error::{AbiError, ErrorKind, MnemonicError, VerifyError},
     id::*,
     info::{AccountInfo, ContractInfo, FileInfo},
+    keystore::Keystore,
+    signer::Signer,
+    solidity_util::SolidityAddress,
     status::Status,
     transaction_id::TransactionId,
     transaction_receipt::TransactionReceipt,
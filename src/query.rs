@@ -31,11 +31,12 @@ use crate::{
         ToProto,
     },
     transaction::{Transaction, TransactionCryptoTransfer},
-    AccountId, Client, ErrorKind, SecretKey, Status,
+    signer::Signer,
+    AccountId, Client, ErrorKind, Status,
 };
 use failure::Error;
 use futures::compat::Compat01As03;
-use futures::{Future};
+use futures::future::BoxFuture;
 use std::{
     marker::PhantomData,
     sync::{
@@ -76,7 +77,9 @@ where
     contract_service: Arc<SmartContractServiceClient>,
     file_service: Arc<FileServiceClient>,
     payment: Option<proto::Transaction::Transaction>,
-    secret: Option<Arc<dyn Fn() -> Result<SecretKey, Error> + Send + Sync>>,
+    // A payment transaction that is still being signed
+    pending_payment: Option<BoxFuture<'static, Result<proto::Transaction::Transaction, Error>>>,
+    signer: Option<Arc<dyn Signer>>,
     operator: Option<AccountId>,
     node: Option<AccountId>,
     inner: Box<dyn ToQueryProto + Send + Sync>,
@@ -93,12 +96,13 @@ where
     {
         Self {
             payment: None,
+            pending_payment: None,
             crypto_service: client.crypto.clone(),
             contract_service: client.contract.clone(),
             file_service: client.file.clone(),
             node: client.node,
             operator: client.operator,
-            secret: client.operator_secret.clone(),
+            signer: client.operator_signer.clone(),
             inner: Box::new(inner),
             phantom: PhantomData,
         }
@@ -108,7 +112,11 @@ where
         &mut self,
         transaction: &mut Transaction<TransactionCryptoTransfer, S>,
     ) -> Result<&mut Self, Error> {
-        self.payment = Some(transaction.build().take_raw()?.tx);
+        let payment = transaction.take_raw();
+
+        self.payment = None;
+        self.pending_payment = Some(Box::pin(async move { Ok(payment.await?.tx) }));
+
         Ok(self)
     }
 
@@ -122,38 +130,33 @@ where
             .block_on(self.get_async())
     }
 
-    fn send(
+    async fn send(
         &mut self,
-    ) -> impl Future<
-        Output = Result<
-            (
-                proto::ResponseHeader::ResponseHeader,
-                proto::Response::Response,
-            ),
-            Error,
-        >,
-    > {
+    ) -> Result<(proto::ResponseHeader::ResponseHeader, proto::Response::Response), Error> {
         use self::proto::Query::Query_oneof_query::*;
 
+        if let Some(payment) = self.pending_payment.take() {
+            self.payment = Some(payment.await?);
+        }
+
         if !self.inner.is_free() && self.payment.is_none() {
             // Attach a payment transaction if this is a non-free query and we
             // have payment details
-            if self.operator.is_some() && self.node.is_some() && self.secret.is_some() {
+            if self.operator.is_some() && self.node.is_some() && self.signer.is_some() {
                 let cost = 100_300_000;
-                self.payment = TransactionCryptoTransfer::new(&Client {
+                let payment = TransactionCryptoTransfer::new(&Client {
                     node: self.node.clone(),
                     operator: self.operator.clone(),
-                    operator_secret: self.secret.clone(),
+                    operator_signer: self.signer.clone(),
                     crypto: self.crypto_service.clone(),
                     file: self.file_service.clone(),
                     contract: self.contract_service.clone(),
                 })
                 .transfer(*self.node.as_ref().unwrap(), cost as i64)
                 .transfer(*self.operator.as_ref().unwrap(), -(cost as i64))
-                .build()
-                .take_raw()
-                .ok()
-                .map(|tx| tx.tx);
+                .take_raw();
+
+                self.payment = Some(payment.await?.tx);
             }
         }
 
@@ -188,6 +191,9 @@ where
                         Some(contractGetInfo(_)) => contract.get_contract_info(o, query),
                         Some(contractGetBytecode(_)) => contract.contract_get_bytecode(o, query),
                         Some(contractCallLocal(_)) => contract.contract_call_local_method(o, query),
+                        Some(ContractGetRecords(_)) => {
+                            contract.get_tx_record_by_contract_id(o, query)
+                        }
 
                         _ => unreachable!(),
                     };
@@ -205,6 +211,13 @@ where
 
                         Status::Ok => Ok((header, response)),
 
+                        // A reverted local call still has a result, with the revert data
+                        Status::ContractRevertExecuted
+                            if response.get_contractCallLocal().has_functionResult() =>
+                        {
+                            Ok((header, response))
+                        }
+
                         pre_check_code => Err(ErrorKind::PreCheck(pre_check_code))?,
                     }
                 } else if let Some(Err(error)) = query_res {
@@ -214,6 +227,7 @@ where
                 };
             }
         }
+        .await
     }
 }
 
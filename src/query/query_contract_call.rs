@@ -1,7 +1,7 @@
 use crate::{
     proto::{self, Query::Query_oneof_query, QueryHeader::QueryHeader, ToProto},
     query::{Query, QueryResponse, ToQueryProto},
-    Client, ContractId, function_result::ContractFunctionResult
+    Client, ContractId, ErrorKind, function_result::ContractFunctionResult
 };
 use failure::Error;
 
@@ -13,6 +13,13 @@ pub struct QueryContractCall {
 }
 
 impl QueryContractCall {
+    /// Gas limit used for local calls made through `PartialContractMessage::call_local`.
+    pub const DEFAULT_GAS: i64 = 100_000;
+
+    /// Maximum result size used for local calls made through
+    /// `PartialContractMessage::call_local`.
+    pub const DEFAULT_MAX_RESULT_SIZE: i64 = 1024;
+
     pub fn new(client: &Client, contract_id: ContractId, gas: i64,
     function_parameters: Vec<u8>, max_result_size: i64) -> Query<Self> {
         Query::new(
@@ -30,7 +37,13 @@ impl QueryResponse for QueryContractCall {
     type Response = ContractFunctionResult;
 
     fn get(mut response: proto::Response::Response) -> Result<Self::Response, Error> {
-        Ok(response.take_contractCallLocal().take_functionResult().into())
+        let result: ContractFunctionResult =
+            response.take_contractCallLocal().take_functionResult().into();
+
+        match result.revert() {
+            Some(revert) => Err(ErrorKind::ContractRevert(revert))?,
+            None => Ok(result),
+        }
     }
 }
 
@@ -0,0 +1,140 @@
+// Signing of transactions by a key that need not be held by the client; a `SecretKey` in
+// memory, or a key in a separate process, an OS keyring or a remote signing service
+use crate::{PublicKey, SecretKey, Signature};
+use failure::{bail, err_msg, Error};
+use futures::future::{self, BoxFuture, FutureExt};
+use parking_lot::Mutex;
+use std::{fmt, sync::Arc};
+use try_from::TryInto;
+
+/// A key that can sign transactions and query payments.
+///
+/// `sign` returns a future, so a signer may wait on another process or the network for its
+/// signature. The future owns all it needs; it is started when the transaction is signed and
+/// awaited when the transaction is executed.
+///
+/// ```ignore
+/// let client = Client::builder("testnet.hedera.com:50131")
+///     .operator("0:0:2".parse()?, remote_signer)
+///     .build()?;
+/// ```
+pub trait Signer: Send + Sync {
+    /// The public key of the signatures of this signer.
+    fn public_key(&self) -> Result<PublicKey, Error>;
+
+    /// Sign `message`.
+    fn sign(&self, message: &[u8]) -> BoxFuture<'static, Result<Signature, Error>>;
+}
+
+impl Signer for SecretKey {
+    #[inline]
+    fn public_key(&self) -> Result<PublicKey, Error> {
+        Ok(self.public())
+    }
+
+    #[inline]
+    fn sign(&self, message: &[u8]) -> BoxFuture<'static, Result<Signature, Error>> {
+        future::ready(Ok(SecretKey::sign(self, message))).boxed()
+    }
+}
+
+// A function that returns the key each time it signs, such as `|| env::var("OPERATOR_SECRET")`;
+// the key (or the string it is parsed from) is zeroed once it has been used
+impl<F, R, E> Signer for F
+where
+    F: Fn() -> R + Send + Sync,
+    R: TryInto<SecretKey, Err = E>,
+    E: fmt::Debug + fmt::Display + Send + Sync + 'static,
+{
+    fn public_key(&self) -> Result<PublicKey, Error> {
+        Ok(self().try_into().map_err(err_msg)?.public())
+    }
+
+    fn sign(&self, message: &[u8]) -> BoxFuture<'static, Result<Signature, Error>> {
+        let signature = self().try_into().map_err(err_msg).map(|secret| secret.sign(message));
+
+        future::ready(signature).boxed()
+    }
+}
+
+/// A test double `Signer`; it signs with a `SecretKey` and records each message it is asked
+/// to sign.
+///
+/// Clones share their record of messages.
+#[derive(Debug, Clone)]
+pub struct TestSigner {
+    secret: SecretKey,
+    fail: bool,
+    messages: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl TestSigner {
+    /// Construct a `TestSigner` that signs with `secret`.
+    pub fn new(secret: SecretKey) -> Self {
+        Self { secret, fail: false, messages: Arc::default() }
+    }
+
+    /// Construct a `TestSigner` whose signatures all fail, as a remote signer that is down.
+    pub fn failing(secret: SecretKey) -> Self {
+        Self { fail: true, ..Self::new(secret) }
+    }
+
+    /// The messages this signer was asked to sign, in order.
+    pub fn messages(&self) -> Vec<Vec<u8>> {
+        self.messages.lock().clone()
+    }
+}
+
+impl Signer for TestSigner {
+    fn public_key(&self) -> Result<PublicKey, Error> {
+        Ok(self.secret.public())
+    }
+
+    fn sign(&self, message: &[u8]) -> BoxFuture<'static, Result<Signature, Error>> {
+        self.messages.lock().push(message.to_vec());
+
+        let secret = self.secret.clone();
+        let fail = self.fail;
+        let message = message.to_vec();
+
+        async move {
+            if fail {
+                bail!("test signer: failed to sign");
+            }
+
+            Ok(secret.sign(&message))
+        }
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET_KEY: &str = "302e020100300506032b657004220420db484b828e64b2d8f12ce3c0a0e93a0b\
+                              8cce7af1bb8f39c97732394482538e10";
+
+    #[test]
+    fn test_signer() -> Result<(), Error> {
+        let secret: SecretKey = SECRET_KEY.parse()?;
+        let signer = TestSigner::new(secret.clone());
+
+        let signature = futures::executor::block_on(Signer::sign(&signer, b"hello"))?;
+
+        assert!(signer.public_key()?.verify(b"hello", &signature)?);
+        assert_eq!(signer.messages(), vec![b"hello".to_vec()]);
+
+        let failing = TestSigner::failing(secret);
+        assert!(futures::executor::block_on(Signer::sign(&failing, b"hello")).is_err());
+
+        // A function of the key signs as the key
+        let function = || SECRET_KEY.parse::<SecretKey>();
+        let signature = futures::executor::block_on(Signer::sign(&function, b"hello"))?;
+
+        assert_eq!(function.public_key()?, signer.public_key()?);
+        assert!(signer.public_key()?.verify(b"hello", &signature)?);
+
+        Ok(())
+    }
+}
@@ -1,96 +1,295 @@
 // Utils for solidity interfaces
 use hex;
+use crate::call_param_utils::illegal_argument;
+use crate::error::AbiError;
 use crate::id::{AccountId, ContractId, FileId};
+use std::{fmt, str::FromStr};
+use try_from::{TryFrom, TryInto};
 
 const ADDRESS_LEN: i8 = 20;
 const ADDRESS_LEN_HEX: i8 = 40;
 
-pub(crate) fn check_address_len(addr: Vec<u8>) {
-    if addr.len() != ADDRESS_LEN as usize {
-        panic!("ILLEGAL ARGUMENT ERROR: Solidity addresses must be 20 bytes or 40 hex chars")
+/// The 20 byte address of an account, contract or file as seen by Solidity contracts.
+///
+/// The address of an entity is its shard (4 bytes), realm (8 bytes) and number (8 bytes),
+/// each big-endian. Addresses are formatted as 40 hex chars; `{:#}` adds a `0x` prefix, and
+/// parsing accepts either form.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SolidityAddress([u8; 20]);
+
+impl SolidityAddress {
+    /// Copy an address from a slice of exactly 20 bytes.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, AbiError> {
+        check_address_len(bytes)?;
+
+        let mut addr = [0; 20];
+        addr.copy_from_slice(bytes);
+        Ok(SolidityAddress(addr))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    fn from_entity(shard: i64, realm: i64, entity: i64) -> Result<Self, AbiError> {
+        if shard < 0 || shard > i64::from(u32::max_value()) {
+            return Err(AbiError::IdOutOfRange { field: "shard", value: shard.into() });
+        }
+
+        if realm < 0 {
+            return Err(AbiError::IdOutOfRange { field: "realm", value: realm.into() });
+        }
+
+        if entity < 0 {
+            return Err(AbiError::IdOutOfRange { field: "entity number", value: entity.into() });
+        }
+
+        let mut addr = [0; 20];
+        addr[..4].copy_from_slice(&(shard as u32).to_be_bytes());
+        addr[4..12].copy_from_slice(&realm.to_be_bytes());
+        addr[12..].copy_from_slice(&entity.to_be_bytes());
+        Ok(SolidityAddress(addr))
+    }
+
+    fn entity(&self) -> Result<(i64, i64, i64), AbiError> {
+        let mut shard_chunk: [u8; 4] = Default::default();
+        shard_chunk.copy_from_slice(&self.0[..4]);
+        let mut realm_chunk: [u8; 8] = Default::default();
+        realm_chunk.copy_from_slice(&self.0[4..12]);
+        let mut entity_chunk: [u8; 8] = Default::default();
+        entity_chunk.copy_from_slice(&self.0[12..]);
+
+        let shard = u32::from_be_bytes(shard_chunk);
+        let realm = u64::from_be_bytes(realm_chunk);
+        let entity = u64::from_be_bytes(entity_chunk);
+
+        // Realm and entity numbers are signed in the protobufs
+        let fits = |field, value: u64| {
+            if value > i64::max_value() as u64 {
+                return Err(AbiError::IdOutOfRange { field, value: value.into() });
+            }
+            Ok(value as i64)
+        };
+
+        Ok((i64::from(shard), fits("realm", realm)?, fits("entity number", entity)?))
     }
 }
 
-pub(crate) fn decode_address(addr: String) -> Vec<u8> {
-    if addr.chars().count() != ADDRESS_LEN_HEX as usize {
-        panic!("ILLEGAL ARGUMENT ERROR: Solidity addresses must be 20 bytes or 40 hex chars")
+impl From<[u8; 20]> for SolidityAddress {
+    fn from(addr: [u8; 20]) -> Self {
+        SolidityAddress(addr)
     }
+}
 
-    let b = match hex::decode(addr) {
-        Ok(a) => a.to_vec(),
-        Err(e) => panic!("ILLEGAL ARGUMENT ERROR: failed to decode Solidity address as hex; \
-        {:#?}", e)
+impl From<SolidityAddress> for [u8; 20] {
+    fn from(addr: SolidityAddress) -> Self {
+        addr.0
+    }
+}
+
+impl AsRef<[u8]> for SolidityAddress {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for SolidityAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for SolidityAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SolidityAddress({:#})", self)
+    }
+}
+
+impl FromStr for SolidityAddress {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = if s.starts_with("0x") || s.starts_with("0X") { &s[2..] } else { s };
+
+        if hex.chars().count() != ADDRESS_LEN_HEX as usize {
+            return Err(AbiError::InvalidAddress(format!(
+                "Solidity addresses must be 20 bytes or 40 hex chars; found {:?}", s)));
+        }
+
+        let bytes = hex::decode(hex).map_err(|e| AbiError::InvalidAddress(format!(
+            "failed to decode {:?} as hex; {}", s, e)))?;
+
+        Self::from_slice(&bytes)
+    }
+}
+
+macro_rules! impl_entity_address {
+    ($name:ident, $field:ident) => {
+        impl TryFrom<$name> for SolidityAddress {
+            type Err = AbiError;
+
+            fn try_from(id: $name) -> Result<Self, AbiError> {
+                SolidityAddress::from_entity(id.shard, id.realm, id.$field)
+            }
+        }
+
+        impl TryFrom<SolidityAddress> for $name {
+            type Err = AbiError;
+
+            fn try_from(addr: SolidityAddress) -> Result<Self, AbiError> {
+                let (shard, realm, $field) = addr.entity()?;
+                Ok($name { shard, realm, $field })
+            }
+        }
     };
-    b
 }
 
-pub(crate) fn address_for_entity(shard: i64, realm: i64, entity: i64) -> String {
-    if shard < i32::min_value() as i64 || shard > i32::max_value() as i64 {
-        panic!("ILLEGAL ARGUMENT ERROR: shard id should be within 32bit range")
+impl_entity_address!(AccountId, account);
+impl_entity_address!(ContractId, contract);
+impl_entity_address!(FileId, file);
+
+pub(crate) fn check_address_len(addr: &[u8]) -> Result<(), AbiError> {
+    if addr.len() != ADDRESS_LEN as usize {
+        return Err(AbiError::InvalidAddress(format!(
+            "Solidity addresses must be 20 bytes or 40 hex chars; found {} bytes", addr.len())));
     }
-    let mut buf = Vec::new();
+    Ok(())
+}
 
-    let s = shard as i32;
-    buf.extend(s.to_be_bytes().to_vec());
-    buf.extend(realm.to_be_bytes().to_vec());
-    buf.extend(entity.to_be_bytes().to_vec());
+pub(crate) fn decode_address(addr: &str) -> Result<Vec<u8>, AbiError> {
+    Ok(addr.parse::<SolidityAddress>()?.to_vec())
+}
 
-    let out = hex::encode(buf);
-    out
+/// The Solidity address of an account, as 40 hex chars.
+///
+/// Fails if the shard does not fit in 32 bits or any part of the ID is negative.
+pub fn try_address_for_account(acct_id: AccountId) -> Result<String, AbiError> {
+    Ok(SolidityAddress::try_from(acct_id)?.to_string())
 }
 
-pub(crate) fn entity_for_address(addr: String) -> (i64, i64, i64) {
-    let decoded_address = decode_address(addr);
+pub fn try_address_for_contract(contract_id: ContractId) -> Result<String, AbiError> {
+    Ok(SolidityAddress::try_from(contract_id)?.to_string())
+}
+
+pub fn try_address_for_file(file_id: FileId) -> Result<String, AbiError> {
+    Ok(SolidityAddress::try_from(file_id)?.to_string())
+}
 
-    let mut shard_chunk: [u8; 4] = Default::default();
-    shard_chunk.copy_from_slice(&decoded_address[..4]);
-    let mut realm_chunk: [u8; 8] = Default::default();
-    realm_chunk.copy_from_slice(&decoded_address[4..12]);
-    let mut entity_chunk: [u8; 8] = Default::default();
-    entity_chunk.copy_from_slice(&decoded_address[12..]);
+/// Parse a Solidity address of 40 hex chars as an account.
+pub fn try_account_for_address(addr: &str) -> Result<AccountId, AbiError> {
+    addr.parse::<SolidityAddress>()?.try_into()
+}
 
-    let shard = unsafe { std::mem::transmute::<[u8; 4], u32>(shard_chunk) }.to_be();
-    let realm = unsafe { std::mem::transmute::<[u8; 8], u64>(realm_chunk) }.to_be();
-    let entity = unsafe { std::mem::transmute::<[u8; 8], u64>(entity_chunk) }.to_be();
+pub fn try_contract_for_address(addr: &str) -> Result<ContractId, AbiError> {
+    addr.parse::<SolidityAddress>()?.try_into()
+}
 
-    (shard as i64, realm as i64, entity as i64)
+pub fn try_file_for_address(addr: &str) -> Result<FileId, AbiError> {
+    addr.parse::<SolidityAddress>()?.try_into()
 }
 
+/// Panics if the ID does not fit in an address; see `try_address_for_account`.
 pub fn address_for_account(acct_id: AccountId) -> String {
-    address_for_entity(acct_id.shard, acct_id.realm, acct_id.account)
+    try_address_for_account(acct_id).unwrap_or_else(illegal_argument)
 }
 
+/// Panics if the ID does not fit in an address; see `try_address_for_contract`.
 pub fn address_for_contract(contract_id: ContractId) -> String {
-    address_for_entity(contract_id.shard, contract_id.realm, contract_id.contract)
+    try_address_for_contract(contract_id).unwrap_or_else(illegal_argument)
 }
 
+/// Panics if the ID does not fit in an address; see `try_address_for_file`.
 pub fn address_for_file(file_id: FileId) -> String {
-    address_for_entity(file_id.shard, file_id.realm, file_id.file)
+    try_address_for_file(file_id).unwrap_or_else(illegal_argument)
 }
 
+/// Panics if `addr` is not a valid address; see `try_account_for_address`.
 pub fn account_for_address(addr: String) -> AccountId {
-    let (shard, realm, account) = entity_for_address(addr);
-    AccountId{
-        shard,
-        realm,
-        account
-    }
+    try_account_for_address(&addr).unwrap_or_else(illegal_argument)
+}
+
+/// Panics if `addr` is not a valid address; see `try_contract_for_address`.
+pub fn contract_for_address(addr: String) -> ContractId {
+    try_contract_for_address(&addr).unwrap_or_else(illegal_argument)
 }
 
+/// Panics if `addr` is not a valid address; see `try_file_for_address`.
+pub fn file_for_address(addr: String) -> FileId {
+    try_file_for_address(&addr).unwrap_or_else(illegal_argument)
+}
+
+#[deprecated(note = "returns a contract ID; use `contract_for_address`")]
 pub fn account_for_contract(addr: String) -> ContractId {
-    let (shard, realm, contract) = entity_for_address(addr);
-    ContractId{
-        shard,
-        realm,
-        contract
-    }
+    contract_for_address(addr)
 }
 
+#[deprecated(note = "returns a file ID; use `file_for_address`")]
 pub fn account_for_file(addr: String) -> FileId {
-    let (shard, realm, file) = entity_for_address(addr);
-    FileId{
-        shard,
-        realm,
-        file
+    file_for_address(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_round_trip() -> Result<(), AbiError> {
+        let id = ContractId { shard: 0, realm: 0, contract: 1001 };
+        let addr = try_address_for_contract(id)?;
+
+        assert_eq!(addr, "00000000000000000000000000000000000003e9");
+        assert_eq!(try_contract_for_address(&addr)?, id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solidity_address() -> Result<(), AbiError> {
+        let id = AccountId { shard: 1, realm: 2, account: 3 };
+        let addr = SolidityAddress::try_from(id)?;
+
+        assert_eq!(addr.to_string(), "0000000100000000000000020000000000000003");
+        assert_eq!(format!("{:#}", addr), "0x0000000100000000000000020000000000000003");
+        assert_eq!(format!("{:#}", addr).parse::<SolidityAddress>()?, addr);
+        assert_eq!(AccountId::try_from(addr)?, id);
+
+        let file: FileId = addr.try_into()?;
+        assert_eq!(file, FileId { shard: 1, realm: 2, file: 3 });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_addresses() {
+        for id in &[
+            AccountId { shard: 1 << 32, realm: 0, account: 2 },
+            AccountId { shard: 0, realm: -1, account: 2 },
+            AccountId { shard: 0, realm: 0, account: -2 },
+        ] {
+            match SolidityAddress::try_from(*id) {
+                Err(AbiError::IdOutOfRange { .. }) => {}
+                result => panic!("expected {} to be rejected; got {:?}", id, result),
+            }
+        }
+
+        let addr = SolidityAddress::from([0xff; 20]);
+        match ContractId::try_from(addr) {
+            Err(AbiError::IdOutOfRange { field: "realm", .. }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        for addr in &["3e9", "0x000000000000000000000000000000000003e9", "zz"] {
+            match try_account_for_address(addr) {
+                Err(AbiError::InvalidAddress(_)) => {}
+                result => panic!("expected {:?} to be rejected; got {:?}", addr, result),
+            }
+        }
     }
 }
@@ -24,7 +24,7 @@ pub use self::{
 };
 
 use crate::{
-    crypto::SecretKey,
+    crypto::Signature,
     error::ErrorKind,
     proto::{
         self,
@@ -33,12 +33,13 @@ use crate::{
         SmartContractService_grpc::{SmartContractService, SmartContractServiceClient},
         ToProto,
     },
+    signer::Signer,
     AccountId, Client, TransactionId,
 };
 use futures::compat::Compat01As03;
 use failure::Error;
-use futures::{Future,};
-use protobuf::Message;
+use futures::{future::BoxFuture, Future};
+use protobuf::{Message, RepeatedField};
 use query_interface::Object;
 use std::{any::Any, marker::PhantomData, mem::swap, sync::Arc, time::Duration};
 
@@ -57,6 +58,8 @@ pub struct TransactionBuilder<T> {
 pub struct TransactionRaw {
     bytes: Vec<u8>,
     pub(crate) tx: proto::Transaction::Transaction,
+    // Signatures from `sign`, in order; awaited when the transaction is taken to be sent
+    signatures: Vec<BoxFuture<'static, Result<Signature, Error>>>,
 }
 
 enum TransactionKind<T> {
@@ -78,7 +81,7 @@ pub struct Transaction<T, S = TransactionBuilder<T>> {
     crypto_service: Arc<CryptoServiceClient>,
     file_service: Arc<FileServiceClient>,
     contract_service: Arc<SmartContractServiceClient>,
-    secret: Option<Arc<dyn Fn() -> Result<SecretKey, Error> + Send + Sync>>,
+    signer: Option<Arc<dyn Signer>>,
     kind: TransactionKind<T>,
     phantom: PhantomData<S>,
 }
@@ -92,7 +95,7 @@ impl<T: 'static> Transaction<T, TransactionBuilder<T>> {
             crypto_service: client.crypto.clone(),
             file_service: client.file.clone(),
             contract_service: client.contract.clone(),
-            secret: client.operator_secret.clone(),
+            signer: client.operator_signer.clone(),
             kind: TransactionKind::Builder(TransactionBuilder {
                 id: client.operator.map(TransactionId::new),
                 node: client.node,
@@ -116,7 +119,7 @@ impl<T: 'static> Transaction<T, TransactionBuilder<T>> {
 
     pub fn operator(&mut self, id: AccountId) -> &mut Self {
         // This resets any default operator we may have had
-        self.secret = None;
+        self.signer = None;
 
         if let Some(state) = self.as_builder() {
             state.id = Some(TransactionId::new(id));
@@ -152,8 +155,8 @@ impl<T: 'static> Transaction<T, TransactionBuilder<T>> {
         self
     }
 
-    pub fn sign(&mut self, secret: &SecretKey) -> &mut Transaction<T, TransactionRaw> {
-        self.build().sign(secret)
+    pub fn sign<S: Signer + ?Sized>(&mut self, signer: &S) -> &mut Transaction<T, TransactionRaw> {
+        self.build().sign(signer)
     }
 
     pub fn execute_async(&mut self) -> impl Future<Output = Result<TransactionId, Error>> {
@@ -166,6 +169,24 @@ impl<T: 'static> Transaction<T, TransactionBuilder<T>> {
             .block_on(self.execute_async())
     }
 
+    // A client with the services and operator of this transaction, to make queries for it
+    // before it is built
+    pub(crate) fn client(&mut self) -> Client {
+        let (node, operator) = match self.as_builder() {
+            Some(state) => (state.node, state.id.as_ref().map(|id| id.account_id)),
+            None => (None, None),
+        };
+
+        Client {
+            node,
+            operator,
+            operator_signer: self.signer.clone(),
+            crypto: self.crypto_service.clone(),
+            file: self.file_service.clone(),
+            contract: self.contract_service.clone(),
+        }
+    }
+
     #[inline]
     fn as_builder(&mut self) -> Option<&mut TransactionBuilder<T>> {
         match &mut self.kind {
@@ -226,35 +247,13 @@ impl<T: 'static> Transaction<T, TransactionRaw> {
         }
     }
 
-    pub fn sign(&mut self, secret: &SecretKey) -> &mut Self {
+    /// Sign the transaction with `signer`, as well as the operator.
+    ///
+    /// The signature is started now, and awaited when the transaction is executed.
+    pub fn sign<S: Signer + ?Sized>(&mut self, signer: &S) -> &mut Self {
         if let Some(state) = self.as_raw() {
-            // note: this cannot fail
-
-            let id = state
-                .tx
-                .get_body()
-                .transactionID
-                .as_ref()
-                .unwrap()
-                .clone();
-
-            // note: this cannot fail
-            let operator = id.accountID.as_ref().unwrap().clone();
-
-            // HACK: If an accountNum is < 1000 pretend it has a slightly more complex key structure
-            let signature = if operator.get_accountNum() < 1000 {
-                (&[&secret.sign(&state.bytes)][..]).to_proto().unwrap()
-            } else {
-                secret.sign(&state.bytes).to_proto().unwrap()
-            };
-
-            if !state.tx.has_sigs() {
-                state.tx.set_sigs(proto::BasicTypes::SignatureList::new());
-            }
-
-            // note: this cannot fail
-            let signatures = &mut state.tx.sigs.as_mut().unwrap().sigs;
-            signatures.push(signature);
+            let signature = signer.sign(&state.bytes);
+            state.signatures.push(signature);
         }
 
         self
@@ -273,7 +272,7 @@ impl<T: 'static> Transaction<T, TransactionRaw> {
         let state = self.take_raw();
 
         async move {
-            let state = state?;
+            let state = state.await?;
 
             let mut tx = state.tx;
             let id = tx
@@ -296,6 +295,7 @@ impl<T: 'static> Transaction<T, TransactionRaw> {
                 //////////////////////// FILE TRANSACTIONS
                 Some(fileCreate(_)) => file.create_file(o, tx),
                 Some(fileAppend(_)) => file.append_content(o, tx),
+                Some(fileDelete(_)) => file.delete_file(o, tx),
                 //////////////////////// CONTRACT TRANSACTIONS
                 Some(contractCreateInstance(_)) => contract.create_contract(o, tx),
                 Some(contractUpdateInstance(_)) => contract.update_contract(o, tx),
@@ -314,74 +314,73 @@ impl<T: 'static> Transaction<T, TransactionRaw> {
 }
 
 impl<T: 'static, S: 'static> Transaction<T, S> {
-    #[inline]
-    pub(crate) fn take_raw(&mut self) -> Result<TransactionRaw, Error> {
-//        use self::proto::Transaction::Transaction_oneof_bodyData::*;
+    // Take the transaction to be sent, once it is signed by each signer and the operator
+    pub(crate) fn take_raw(&mut self) -> impl Future<Output = Result<TransactionRaw, Error>> {
+        let signer = self.signer.clone();
+        let state = match self.build().kind.take() {
+            TransactionKind::Raw(state) => Ok(state),
+            TransactionKind::Err(err) => Err(err),
 
-        match self.kind.take() {
-            TransactionKind::Builder(_) => self.build().take_raw(),
+            // not possible to get here; `build` leaves the transaction raw or failed
+            _ => unreachable!(),
+        };
 
-            TransactionKind::Raw(mut state) => {
-                let tx = &mut state.tx;
+        async move {
+            let mut state = state?;
 
-                // note: cannot fail
-                let id = tx
-                    .get_body()
-                    .transactionID
-                    .as_ref()
-                    .unwrap()
-                    .clone();
+            // note: cannot fail
+            let operator = state
+                .tx
+                .get_body()
+                .transactionID
+                .as_ref()
+                .unwrap()
+                .accountID
+                .as_ref()
+                .unwrap()
+                .clone();
 
-                let operator = id.accountID.as_ref().unwrap().clone();
+            let mut signatures = Vec::with_capacity(state.signatures.len() + 1);
 
-                if !tx.has_sigs() {
-                    // If .sign was never called this will be still need to be initialized
-                    tx.set_sigs(proto::BasicTypes::SignatureList::new());
-                }
+            for signature in state.signatures.drain(..) {
+                signatures.push(signature_to_proto(&operator, &signature.await?));
+            }
 
-                if let Some(secret) = &self.secret {
-                    // HACK: If an accountNum is < 1000 pretend it has a slightly more complex key structure
-                    let signature = if operator.get_accountNum() < 1000 {
-                        (&[&secret()?.sign(&state.bytes)][..]).to_proto().unwrap()
-                    } else {
-                        secret()?.sign(&state.bytes).to_proto().unwrap()
-                    };
-
-                    match &tx.get_body().clone().data {
-                        Some(cryptoTransfer(data)) => {
-                            // Insert a signature for the operator if the operator
-                            // is sending any monies
-                            for transfer in &data.transfers.as_ref().unwrap().accountAmounts {
-                                if transfer.accountID.as_ref().unwrap() == &operator {
-                                    tx.sigs.as_mut().unwrap().sigs.push(signature.clone());
-                                }
-                            }
-                        }
+            if let Some(signer) = signer {
+                let signature = signature_to_proto(&operator, &signer.sign(&state.bytes).await?);
 
-                        _ => {}
+                if let Some(cryptoTransfer(data)) = &state.tx.get_body().data {
+                    // Insert a signature for the operator if the operator
+                    // is sending any monies
+                    for transfer in &data.transfers.as_ref().unwrap().accountAmounts {
+                        if transfer.accountID.as_ref().unwrap() == &operator {
+                            signatures.push(signature.clone());
+                        }
                     }
-
-                    // Sign as the operator of the transaction
-                    tx.sigs.as_mut().unwrap().sigs.insert(0, signature);
                 }
 
-                match tx.mut_body().data {
-                    Some(cryptoDelete(ref mut data)) => {
-                        if !data.has_transferAccountID() {
-                            // default the transfer account ID to the operator of the transaction
-                            data.set_transferAccountID(operator);
-                        }
-                    }
+                // Sign as the operator of the transaction
+                signatures.insert(0, signature);
+            }
+
+            let tx = &mut state.tx;
 
-                    _ => {}
+            let mut list = proto::BasicTypes::SignatureList::new();
+            list.set_sigs(RepeatedField::from_vec(signatures));
+            tx.set_sigs(list);
+
+            match tx.mut_body().data {
+                Some(cryptoDelete(ref mut data)) => {
+                    if !data.has_transferAccountID() {
+                        // default the transfer account ID to the operator of the transaction
+                        data.set_transferAccountID(operator);
+                    }
                 }
 
-                Ok(state)
+                _ => {}
             }
 
-            TransactionKind::Err(err) => Err(err),
-
-            TransactionKind::Empty => panic!("transaction already executed"),
+            Ok(state)
         }
     }
 
@@ -410,7 +409,8 @@ impl<T: 'static, S: 'static> Transaction<T, S> {
                     let tx: proto::Transaction::Transaction = tx;
                     let bytes = tx.get_body().write_to_bytes().unwrap();
 
-                    self.kind = TransactionKind::Raw(TransactionRaw { tx, bytes })
+                    self.kind =
+                        TransactionKind::Raw(TransactionRaw { tx, bytes, signatures: Vec::new() })
                 }
 
                 Err(error) => {
@@ -424,6 +424,19 @@ impl<T: 'static, S: 'static> Transaction<T, S> {
     }
 }
 
+// HACK: If an accountNum is < 1000 pretend it has a slightly more complex key structure
+fn signature_to_proto(
+    operator: &proto::BasicTypes::AccountID,
+    signature: &Signature,
+) -> proto::BasicTypes::Signature {
+    // note: this cannot fail
+    if operator.get_accountNum() < 1000 {
+        (&[signature][..]).to_proto().unwrap()
+    } else {
+        signature.to_proto().unwrap()
+    }
+}
+
 impl<T> ToProto<proto::Transaction::Transaction> for TransactionBuilder<T> {
     fn to_proto(&self) -> Result<proto::Transaction::Transaction, Error> {
         let mut tx = proto::Transaction::Transaction::new();
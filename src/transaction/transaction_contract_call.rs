@@ -1,7 +1,9 @@
 use crate::{
+    abi::{AbiValue, ContractAbi, Function},
+    gas::GasEstimator,
     proto::{self, ToProto, TransactionBody::TransactionBody_oneof_data},
     transaction::Transaction,
-    Client, ContractId,
+    AbiError, Client, ContractId,
 };
 use failure::Error;
 use query_interface::{interfaces, vtable_for};
@@ -54,6 +56,35 @@ impl Transaction<TransactionContractCall> {
         self.inner().function_parameters = params;
         self
     }
+
+    /// Estimate the gas needed for this call and use it as the gas limit; see `GasEstimator`.
+    ///
+    /// The estimate is made with the function parameters that are set when this is called.
+    pub fn gas_auto(&mut self) -> Result<&mut Self, Error> {
+        crate::RUNTIME
+            .lock()
+            .block_on(self.gas_auto_async())
+    }
+
+    pub async fn gas_auto_async(&mut self) -> Result<&mut Self, Error> {
+        let client = self.client();
+        let (id, params) = {
+            let inner = self.inner();
+            (inner.id, inner.function_parameters.clone())
+        };
+
+        let estimate = GasEstimator::new(&client, id, params).get_async().await?;
+        Ok(self.gas(estimate.gas))
+    }
+
+    /// Find the function of `abi` this call is for, by its selector, and decode the parameters
+    /// it is called with.
+    pub fn decode_function_parameters<'a>(
+        &mut self,
+        abi: &'a ContractAbi,
+    ) -> Result<(&'a Function, Vec<AbiValue>), AbiError> {
+        abi.decode_call(&self.inner().function_parameters)
+    }
 }
 
 impl ToProto<TransactionBody_oneof_data> for TransactionContractCall {
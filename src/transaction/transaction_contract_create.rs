@@ -1,7 +1,8 @@
 use crate::{
     crypto::PublicKey,
+    gas::{self, DEFAULT_GAS_MARGIN},
     proto::{self, ToProto, TransactionBody::TransactionBody_oneof_data},
-    AccountId, FileId,
+    AccountId, ErrorKind, FileId,
 };
 
 use crate::{transaction::Transaction, Client};
@@ -54,6 +55,37 @@ impl Transaction<TransactionContractCreate> {
         self
     }
 
+    /// Estimate the gas needed to create the contract from the size of its bytecode and use it
+    /// as the gas limit; see `gas::estimate_create_gas`.
+    ///
+    /// The bytecode is read from the file, so the file and the constructor parameters must be
+    /// set when this is called.
+    pub fn gas_auto(&mut self) -> Result<&mut Self, Error> {
+        crate::RUNTIME
+            .lock()
+            .block_on(self.gas_auto_async())
+    }
+
+    pub async fn gas_auto_async(&mut self) -> Result<&mut Self, Error> {
+        let client = self.client();
+        let (file, params) = {
+            let inner = self.inner();
+            (inner.file_id, inner.constructor_parameters.clone().unwrap_or_default())
+        };
+
+        let file = match file {
+            Some(file) => file,
+            None => Err(ErrorKind::MissingField("file"))?,
+        };
+
+        // The file contains the hex-encoded bytecode
+        let contents = client.file(file).contents().get_async().await?;
+        let bytecode = hex::decode(&contents)?;
+
+        let estimate = gas::estimate_create_gas(&bytecode, &params, DEFAULT_GAS_MARGIN);
+        Ok(self.gas(estimate.gas))
+    }
+
     #[inline]
     pub fn admin_key(&mut self, key: PublicKey) -> &mut Self {
         self.inner().admin_key = Some(key);
@@ -1,4 +1,7 @@
-use crate::{id::AccountId, proto, function_result::ContractFunctionResult, TransactionReceipt};
+use crate::{
+    abi::ContractRevert, function_result::ContractFunctionResult, id::AccountId, proto,
+    TransactionReceipt,
+};
 use chrono::{DateTime, Utc};
 use failure::{err_msg, Error};
 use try_from::{TryFrom, TryInto};
@@ -20,6 +23,19 @@ pub struct TransactionRecord {
     pub body: TransactionRecordBody,
 }
 
+impl TransactionRecord {
+    /// The reason the contract call or contract creation of this transaction reverted, if it
+    /// did.
+    pub fn contract_revert(&self) -> Option<ContractRevert> {
+        match &self.body {
+            TransactionRecordBody::ContractCall(result)
+            | TransactionRecordBody::ContractCreate(result) => result.revert(),
+
+            TransactionRecordBody::Transfer(_) => None,
+        }
+    }
+}
+
 impl TryFrom<proto::TransactionRecord::TransactionRecord> for TransactionRecord {
     type Err = Error;
 
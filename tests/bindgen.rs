@@ -0,0 +1,90 @@
+// Generated contract bindings must compile against the crate and work when called
+use hedera::abi::{Bindings, ContractAbi};
+use std::{fs, path::Path};
+
+const ABI: &str = r#"[
+    { "type": "constructor", "inputs": [{ "name": "_supply", "type": "uint256" }] },
+    {
+        "type": "function",
+        "name": "balanceOf",
+        "inputs": [{ "name": "_owner", "type": "address" }],
+        "outputs": [{ "name": "balance", "type": "uint256" }],
+        "stateMutability": "view"
+    },
+    {
+        "type": "function",
+        "name": "transfer",
+        "inputs": [
+            { "name": "to", "type": "address" },
+            { "name": "type", "type": "uint8" }
+        ],
+        "outputs": [{ "name": "", "type": "bool" }, { "name": "", "type": "int24" }]
+    },
+    {
+        "type": "function",
+        "name": "transfer",
+        "inputs": [{ "name": "points", "type": "tuple[]", "components": [
+            { "name": "x", "type": "uint64" },
+            { "name": "y", "type": "string" }
+        ] }],
+        "outputs": []
+    },
+    {
+        "type": "event",
+        "name": "Transfer",
+        "inputs": [
+            { "name": "from", "type": "address", "indexed": true },
+            { "name": "value", "type": "uint256", "indexed": false }
+        ]
+    },
+    {
+        "type": "event",
+        "name": "Transfer",
+        "inputs": [
+            { "name": "from", "type": "address", "indexed": true },
+            { "name": "to", "type": "address", "indexed": true },
+            { "name": "value", "type": "uint256", "indexed": false }
+        ]
+    }
+]"#;
+
+const MAIN: &str = r#"
+fn main() {
+    use hedera::abi::AbiValue;
+
+    let owner: hedera::SolidityAddress =
+        "00000000000000000000000000000000000003e9".parse().unwrap();
+
+    // `balanceOf(address)`
+    let data = Token::balance_of_params(owner).unwrap().assemble();
+    assert_eq!(data[..4], [0x70, 0xa0, 0x82, 0x31]);
+    assert_eq!(data.len(), 4 + 32);
+
+    let data = Token::transfer_1_params(vec![(1, "a".to_string())]).unwrap().assemble();
+    assert_eq!(data.len(), 4 + 32 * 7);
+
+    assert!(Token::constructor_params(1000u32.into()).is_ok());
+
+    let event = Transfer1Event::from_values(vec![
+        AbiValue::Address(owner),
+        AbiValue::Address(owner),
+        AbiValue::Uint(5u8.into()),
+    ])
+    .unwrap();
+
+    assert_eq!(event.value, hedera::abi::BigUint::from(5u8));
+    assert_eq!(Transfer1Event::SIGNATURE, "Transfer(address,address,uint256)");
+    assert!(TransferEvent::from_values(Vec::new()).is_err());
+}
+"#;
+
+#[test]
+fn test_generated_bindings_compile() {
+    let abi = ContractAbi::from_json(ABI).unwrap();
+    let code = Bindings::new("Token", abi).generate();
+
+    let path = Path::new(env!("OUT_DIR")).join("bindgen_token.rs");
+    fs::write(&path, format!("{}{}", code, MAIN)).unwrap();
+
+    trybuild::TestCases::new().pass(&path);
+}